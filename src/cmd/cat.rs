@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use csv;
 
 use CliResult;
@@ -16,7 +18,10 @@ When concatenating by row, all CSV data must have the same number of columns.
 If you need to rearrange the columns or fix the lengths of records, use the
 'select' or 'fixlengths' commands. Also, only the headers of the *first* CSV
 data given are used. Headers in subsequent inputs are ignored. (This behavior
-can be disabled with --no-headers.)
+can be disabled with --no-headers.) To guard against inputs whose columns
+silently misalign, use --check-headers to require that every input has the
+same headers as the first, or --by-name to reorder the columns of each input
+to match the headers of the first.
 
 Usage:
     xsv cat rows    [options] [<input>...]
@@ -27,6 +32,17 @@ cat options:
     -p, --pad              When concatenating columns, this flag will cause
                            all records to appear. It will pad each row if
                            other CSV data isn't long enough.
+    --check-headers        When concatenating rows, fail if the headers of
+                           any input differ (by name or order) from the
+                           headers of the first input.
+    --by-name              When concatenating rows, reorder the columns of
+                           each input to match the headers of the first
+                           input. Every input must have the same set of
+                           column names, in any order. Repeated names are
+                           matched in the order in which they appear.
+    --source <name>        When concatenating rows, prepend a column named
+                           <name> containing the file path that each row
+                           came from.
 
 Common options:
     -h, --help             Display this message
@@ -36,6 +52,8 @@ Common options:
                            concatenating columns.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
@@ -44,6 +62,9 @@ struct Args {
     cmd_columns: bool,
     arg_input: Vec<String>,
     flag_pad: bool,
+    flag_check_headers: bool,
+    flag_by_name: bool,
+    flag_source: Option<String>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
@@ -68,21 +89,111 @@ impl Args {
              .map_err(From::from)
     }
 
+    /// Returns a name for the `i`th input suitable for error messages.
+    fn input_name(&self, i: usize) -> &str {
+        self.arg_input.get(i).map(|s| &**s).unwrap_or("<stdin>")
+    }
+
     fn cat_rows(&self) -> CliResult<()> {
+        if self.flag_no_headers
+                && (self.flag_check_headers || self.flag_by_name) {
+            return fail!("--check-headers and --by-name cannot be used \
+                          with --no-headers.");
+        }
         let mut row = csv::ByteRecord::new();
+        let mut out = csv::ByteRecord::new();
         let mut wtr = Config::new(&self.flag_output).writer()?;
+        let mut first_headers = csv::ByteRecord::new();
         for (i, conf) in self.configs()?.into_iter().enumerate() {
             let mut rdr = conf.reader()?;
             if i == 0 {
-                conf.write_headers(&mut rdr, &mut wtr)?;
+                first_headers = rdr.byte_headers()?.clone();
+                match self.flag_source {
+                    None => conf.write_headers(&mut rdr, &mut wtr)?,
+                    Some(ref name) => {
+                        if !self.flag_no_headers && !first_headers.is_empty() {
+                            let mut hdr = csv::ByteRecord::new();
+                            hdr.push_field(name.as_bytes());
+                            hdr.extend(&first_headers);
+                            wtr.write_byte_record(&hdr)?;
+                        }
+                    }
+                }
             }
+            let headers = rdr.byte_headers()?.clone();
+            if self.flag_check_headers && headers != first_headers {
+                return fail!(format!(
+                    "Headers of '{}' differ from the headers of '{}'.",
+                    self.input_name(i), self.input_name(0)));
+            }
+            let order =
+                if self.flag_by_name {
+                    Some(self.column_order(&first_headers, &headers, i)?)
+                } else {
+                    None
+                };
+            let source = self.flag_source.as_ref()
+                .map(|_| self.input_name(i).as_bytes());
             while rdr.read_byte_record(&mut row)? {
-                wtr.write_byte_record(&row)?;
+                if source.is_none() && order.is_none() {
+                    wtr.write_byte_record(&row)?;
+                    continue;
+                }
+                out.clear();
+                if let Some(source) = source {
+                    out.push_field(source);
+                }
+                match order {
+                    None => out.extend(&row),
+                    Some(ref order) => {
+                        for &j in order {
+                            out.push_field(&row[j]);
+                        }
+                    }
+                }
+                wtr.write_byte_record(&out)?;
             }
         }
         wtr.flush().map_err(From::from)
     }
 
+    /// Returns, for each column in `first`, the index of the column with
+    /// the same name in `headers`, which belong to the `i`th input.
+    ///
+    /// When a name appears several times, its `k`th occurrence in `first`
+    /// is matched with its `k`th occurrence in `headers`.
+    fn column_order(
+        &self,
+        first: &csv::ByteRecord,
+        headers: &csv::ByteRecord,
+        i: usize,
+    ) -> CliResult<Vec<usize>> {
+        if first.len() != headers.len() {
+            return fail!(format!(
+                "'{}' has {} columns but '{}' has {} columns.",
+                self.input_name(i), headers.len(),
+                self.input_name(0), first.len()));
+        }
+        let mut order = Vec::with_capacity(first.len());
+        let mut seen: HashMap<&[u8], usize> = HashMap::new();
+        for name in first.iter() {
+            let k = seen.entry(name).or_insert(0);
+            let j = headers.iter()
+                .enumerate()
+                .filter(|&(_, h)| h == name)
+                .map(|(j, _)| j)
+                .nth(*k);
+            *k += 1;
+            match j {
+                Some(j) => order.push(j),
+                None => return fail!(format!(
+                    "'{}' has no column named '{}'.",
+                    self.input_name(i), String::from_utf8_lossy(name))),
+            }
+        }
+        Ok(order)
+    }
+
     fn cat_columns(&self) -> CliResult<()> {
         let mut wtr = Config::new(&self.flag_output).writer()?;
         let mut rdrs = self.configs()?
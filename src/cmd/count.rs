@@ -1,7 +1,11 @@
+use std::collections::HashSet;
+use std::io::{self, Read};
+
 use csv;
 
 use CliResult;
 use config::{Delimiter, Config};
+use select::SelectColumns;
 use util;
 
 static USAGE: &'static str = "
@@ -10,20 +14,43 @@ Prints a count of the number of records in the CSV data.
 Note that the count will not include the header row (unless --no-headers is
 given).
 
+The --lines and --bytes flags instead count the raw physical lines and bytes
+of the input, like 'wc'. These are not CSV aware: a record with a quoted
+field containing a newline counts as several lines, and the header row is
+always included.
+
+The --distinct flag instead counts the number of distinct combinations of
+values in the selected columns. Empty fields are counted like any other
+value.
+
 Usage:
     xsv count [options] [<input>]
 
+count options:
+    --lines                Print the number of physical lines in the input
+                           instead of the number of records.
+    --bytes                Print the number of bytes in the input instead of
+                           the number of records.
+    --distinct <arg>       Print the number of distinct values in the given
+                           columns instead of the number of records. See
+                           'xsv select --help' for the format details.
+
 Common options:
     -h, --help             Display this message
     -n, --no-headers       When set, the first row will not be included in
                            the count.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
+    flag_lines: bool,
+    flag_bytes: bool,
+    flag_distinct: Option<SelectColumns>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
 }
@@ -34,6 +61,24 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers);
 
+    let modes = [
+        args.flag_lines, args.flag_bytes, args.flag_distinct.is_some(),
+    ];
+    if modes.iter().filter(|&&yes| yes).count() > 1 {
+        return fail!("Only one of --lines, --bytes and --distinct \
+                      may be given.");
+    }
+    if args.flag_lines || args.flag_bytes {
+        let (lines, bytes) = raw_counts(conf.io_reader()?)?;
+        println!("{}", if args.flag_lines { lines } else { bytes });
+        return Ok(());
+    }
+
+    if let Some(sel) = args.flag_distinct {
+        println!("{}", count_distinct(conf.select(sel))?);
+        return Ok(());
+    }
+
     let count =
         match conf.indexed()? {
             Some(idx) => idx.count(),
@@ -49,3 +94,43 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         };
     Ok(println!("{}", count))
 }
+
+/// Returns the number of lines and bytes in `rdr`.
+///
+/// A final line that isn't terminated by a newline is still counted.
+fn raw_counts<R: Read>(mut rdr: R) -> io::Result<(u64, u64)> {
+    let mut buf = vec![0; 64 * 1024];
+    let (mut lines, mut bytes) = (0u64, 0u64);
+    let mut last = b'\n';
+    loop {
+        let n = match rdr.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        lines += buf[..n].iter().filter(|&&b| b == b'\n').count() as u64;
+        bytes += n as u64;
+        last = buf[n - 1];
+    }
+    if last != b'\n' {
+        lines += 1;
+    }
+    Ok((lines, bytes))
+}
+
+/// Returns the number of distinct combinations of values in the columns
+/// selected by `conf`.
+fn count_distinct(conf: Config) -> CliResult<usize> {
+    let mut rdr = conf.reader()?;
+    let sel = conf.selection(rdr.byte_headers()?)?;
+    let mut seen = HashSet::new();
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        let key: Vec<_> = sel.select(&record).map(|f| f.to_vec()).collect();
+        seen.insert(key);
+    }
+    Ok(seen.len())
+}
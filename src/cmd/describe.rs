@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use stats::Commute;
+use tabwriter::TabWriter;
+
+use CliResult;
+use cmd::stats::FieldType;
+use config::{Config, Delimiter};
+use util;
+
+static USAGE: &'static str = "
+Prints a quick overview of CSV data.
+
+The overview shows the number of records and columns, followed by one line
+for each column with its inferred type (like 'xsv stats'), the percentage of
+its values that are empty and the number of distinct values it contains.
+
+This is meant to be read by humans. Use 'xsv stats' or 'xsv schema' for
+output that can be processed further.
+
+Usage:
+    xsv describe [options] [<input>]
+
+describe options:
+    --sample <n>           Only describe the first <n> records. The record
+                           count then reflects the number of records that
+                           were described.
+
+Common options:
+    -h, --help             Display this message
+    -n, --no-headers       When set, the first row will NOT be interpreted
+                           as column names. i.e., It will be described
+                           along with the other records.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_sample: Option<u64>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .head(args.flag_sample);
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let mut columns: Vec<Column> =
+        (0..headers.len()).map(|_| Column::default()).collect();
+    let mut count = 0u64;
+    for row in rconfig.limit_records(rdr.byte_records()) {
+        let row = row?;
+        count += 1;
+        for (col, field) in columns.iter_mut().zip(row.iter()) {
+            col.add(field);
+        }
+    }
+
+    let mut wtr = TabWriter::new(io::stdout());
+    writeln!(&mut wtr, "records\t{}", count)?;
+    writeln!(&mut wtr, "columns\t{}", headers.len())?;
+    writeln!(&mut wtr)?;
+    wtr.flush()?;
+
+    let mut wtr = TabWriter::new(io::stdout());
+    writeln!(&mut wtr, "field\ttype\tempty\tdistinct")?;
+    for (i, (header, col)) in headers.iter().zip(columns).enumerate() {
+        if args.flag_no_headers {
+            write!(&mut wtr, "{}", i + 1)?;
+        } else {
+            wtr.write_all(header)?;
+        }
+        let empty =
+            if count == 0 {
+                0.0
+            } else {
+                100.0 * col.empty as f64 / count as f64
+            };
+        writeln!(&mut wtr, "\t{}\t{:.1}%\t{}",
+                 col.typ, empty, col.distinct.len())?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct Column {
+    typ: FieldType,
+    empty: u64,
+    distinct: HashSet<Vec<u8>>,
+}
+
+impl Column {
+    fn add(&mut self, sample: &[u8]) {
+        self.typ.merge(FieldType::from_sample(sample));
+        if sample.is_empty() {
+            self.empty += 1;
+        }
+        if !self.distinct.contains(sample) {
+            self.distinct.insert(sample.to_vec());
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::iter::once;
+
+use csv;
+
+use CliResult;
+use cmd::join::{ValueIndex, get_row_key};
+use config::{Config, Delimiter};
+use select::SelectColumns;
+use util;
+
+static USAGE: &'static str = "
+Compares two versions of CSV data whose rows are identified by a key.
+
+Rows of <new> whose key isn't in <old> are 'added', rows of <old> whose key
+isn't in <new> are 'removed' and rows whose key is in both but whose other
+fields differ are 'changed'. Rows that didn't change aren't written.
+
+The output has a 'status' column, followed by the row (from <new>, except for
+removed rows) and a 'changed' column with the comma separated names of the
+columns that changed. Added and changed rows are written in the order of
+<new>, followed by the removed rows in the order of <old>.
+
+Like in 'xsv join', keys are compared ignoring leading and trailing
+whitespace. When a key appears several times, its rows are paired up in the
+order in which they appear. Both inputs must have the same columns, in the
+same order.
+
+  $ xsv diff --key id old.csv new.csv
+
+Usage:
+    xsv diff [options] --key <cols> <old> <new>
+    xsv diff --help
+
+diff options:
+    -k, --key <cols>       The columns identifying each row.
+                           See 'xsv select --help' for the format details.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. Changed columns are then given by
+                           their index, starting at 1.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_old: String,
+    arg_new: String,
+    flag_key: SelectColumns,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let old_conf = Config::new(&Some(args.arg_old.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_key.clone());
+    let new_conf = Config::new(&Some(args.arg_new.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_key);
+
+    let mut old_rdr = old_conf.reader_file()?;
+    let mut new_rdr = new_conf.reader()?;
+    let old_headers = old_rdr.byte_headers()?.clone();
+    let new_headers = new_rdr.byte_headers()?.clone();
+    if old_headers.len() != new_headers.len() {
+        return fail!(format!(
+            "Both inputs must have the same number of columns, but found \
+             {} and {} columns.", old_headers.len(), new_headers.len()));
+    }
+    let old_sel = old_conf.selection(&old_headers)?;
+    let new_sel = new_conf.selection(&new_headers)?;
+
+    let names: Vec<Vec<u8>> =
+        if new_conf.no_headers {
+            (1..new_headers.len() + 1)
+                .map(|i| i.to_string().into_bytes())
+                .collect()
+        } else {
+            new_headers.iter().map(|h| h.to_vec()).collect()
+        };
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !new_conf.no_headers {
+        wtr.write_record(once(&b"status"[..])
+            .chain(&new_headers)
+            .chain(once(&b"changed"[..])))?;
+    }
+
+    let mut validx = ValueIndex::new(old_rdr, &old_sel, false, true)?;
+    let mut matched = vec![false; validx.num_rows];
+    let mut old_row = csv::ByteRecord::new();
+    let mut row = csv::ByteRecord::new();
+    while new_rdr.read_byte_record(&mut row)? {
+        let key = get_row_key(&new_sel, &row, false);
+        let rowi = validx.values.get(&key).and_then(|rows| {
+            rows.iter().cloned().find(|&i| !matched[i])
+        });
+        let rowi = match rowi {
+            None => {
+                wtr.write_record(once(&b"added"[..])
+                    .chain(&row)
+                    .chain(once(&b""[..])))?;
+                continue;
+            }
+            Some(rowi) => rowi,
+        };
+        matched[rowi] = true;
+        validx.idx.seek(rowi as u64)?;
+        validx.idx.read_byte_record(&mut old_row)?;
+        let changed: Vec<&[u8]> = names.iter()
+            .enumerate()
+            .filter(|&(i, _)| old_row.get(i) != row.get(i))
+            .map(|(_, name)| &**name)
+            .collect();
+        if !changed.is_empty() {
+            let changed = changed.join(&b","[..]);
+            wtr.write_record(once(&b"changed"[..])
+                .chain(&row)
+                .chain(once(&*changed)))?;
+        }
+    }
+    for (rowi, &matched) in matched.iter().enumerate() {
+        if !matched {
+            validx.idx.seek(rowi as u64)?;
+            validx.idx.read_byte_record(&mut old_row)?;
+            wtr.write_record(once(&b"removed"[..])
+                .chain(&old_row)
+                .chain(once(&b""[..])))?;
+        }
+    }
+    Ok(wtr.flush()?)
+}
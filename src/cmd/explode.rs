@@ -0,0 +1,206 @@
+use std::collections::BTreeSet;
+
+use csv;
+use serde_json::{self, Value};
+
+use CliResult;
+use config::{Config, Delimiter};
+use select::SelectColumns;
+use util;
+
+static USAGE: &'static str = "
+Expands a column containing a JSON array of objects into one row per object.
+
+Each row is repeated once for every object in the array of the given column,
+and the fields of that object are written as new columns in place of the JSON
+column. Nested objects are flattened, so {\"a\": {\"b\": 1}} gives a column
+named 'a.b'. Nested arrays are written as JSON text. The new columns are sorted
+by name, and cells for fields that an object doesn't have are left empty. A
+row with an empty cell or an empty array is written once, with empty cells.
+
+For example, given this data:
+
+    id,items
+    1,\"[{\"\"sku\"\": \"\"a\"\", \"\"qty\"\": 2}, {\"\"sku\"\": \"\"b\"\"}]\"
+    2,[]
+
+running 'xsv explode items' gives:
+
+    id,qty,sku
+    1,2,a
+    1,,b
+    2,,
+
+This command requires reading all CSV data into memory.
+
+Usage:
+    xsv explode [options] <column> [<input>]
+    xsv explode --help
+
+explode options:
+    --on-error <policy>    What to do with a row whose cell isn't a JSON array
+                           of objects. 'fail' stops with an error, 'skip'
+                           drops the row and 'keep' writes the row once, with
+                           empty cells. [default: fail]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. The new columns then have no names.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_on_error: OnError,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum OnError {
+    Fail,
+    Skip,
+    Keep,
+}
+
+/// The flattened fields of one object in a JSON array.
+type Fields = Vec<(String, Vec<u8>)>;
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    if sel.len() != 1 {
+        return fail!(format!("<column> must select exactly one column, \
+                              but it selected {}.", sel.len()));
+    }
+    let column = sel[0];
+
+    let mut keys = BTreeSet::new();
+    let mut rows: Vec<(csv::ByteRecord, Vec<Fields>)> = vec![];
+    let mut row = csv::ByteRecord::new();
+    let mut i = 0;
+    while rdr.read_byte_record(&mut row)? {
+        i += 1;
+        let objects = match parse_objects(&row[column]) {
+            Ok(objects) => objects,
+            Err(err) => match args.flag_on_error {
+                OnError::Fail => {
+                    return fail!(format!("record {}: {}", i, err));
+                }
+                OnError::Skip => continue,
+                OnError::Keep => vec![],
+            },
+        };
+        for fields in &objects {
+            keys.extend(fields.iter().map(|f| f.0.clone()));
+        }
+        rows.push((without(&row, column), objects));
+    }
+
+    let keys: Vec<String> = keys.into_iter().collect();
+    if !rconfig.no_headers {
+        let new = keys.iter().map(|k| k.as_bytes());
+        let parent = without(&headers, column);
+        wtr.write_byte_record(&splice(&parent, column, new))?;
+    }
+    for (parent, objects) in rows {
+        if objects.is_empty() {
+            let new = keys.iter().map(|_| &b""[..]);
+            wtr.write_byte_record(&splice(&parent, column, new))?;
+        }
+        for fields in objects {
+            let new = keys.iter().map(|key| {
+                match fields.iter().find(|f| &f.0 == key) {
+                    None => &b""[..],
+                    Some(f) => &*f.1,
+                }
+            });
+            wtr.write_byte_record(&splice(&parent, column, new))?;
+        }
+    }
+    Ok(wtr.flush()?)
+}
+
+/// Returns `record` without its field at `column`.
+fn without(record: &csv::ByteRecord, column: usize) -> csv::ByteRecord {
+    record.iter()
+        .enumerate()
+        .filter(|&(j, _)| j != column)
+        .map(|(_, field)| field)
+        .collect()
+}
+
+/// Returns `parent` with the fields of `new` inserted at `column`, i.e.,
+/// where the JSON column was.
+fn splice<'a, I>(parent: &csv::ByteRecord, column: usize, new: I)
+    -> csv::ByteRecord
+    where I: Iterator<Item=&'a [u8]>
+{
+    let mut record = csv::ByteRecord::new();
+    record.extend(parent.iter().take(column));
+    record.extend(new);
+    record.extend(parent.iter().skip(column));
+    record
+}
+
+/// Parses a cell holding a JSON array of objects into the flattened fields
+/// of each object. An empty cell is an empty array.
+fn parse_objects(cell: &[u8]) -> Result<Vec<Fields>, String> {
+    if cell.iter().all(|b| b.is_ascii_whitespace()) {
+        return Ok(vec![]);
+    }
+    let value: Value = serde_json::from_slice(cell)
+        .map_err(|err| format!("invalid JSON: {}", err))?;
+    let elements = match value {
+        Value::Array(elements) => elements,
+        _ => return Err("expected a JSON array".to_owned()),
+    };
+    elements.into_iter().map(|element| {
+        let mut fields = vec![];
+        match element {
+            Value::Object(_) => flatten("", element, &mut fields),
+            _ => return Err("expected an array of JSON objects".to_owned()),
+        }
+        Ok(fields)
+    }).collect()
+}
+
+/// Pushes `value` to `fields` under the name `name`, recursing into
+/// objects with dotted names.
+fn flatten(name: &str, value: Value, fields: &mut Fields) {
+    let cell = match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let name =
+                    if name.is_empty() {
+                        key
+                    } else {
+                        format!("{}.{}", name, key)
+                    };
+                flatten(&name, value, fields);
+            }
+            return;
+        }
+        Value::Null => vec![],
+        Value::String(s) => s.into_bytes(),
+        value => value.to_string().into_bytes(),
+    };
+    fields.push((name.to_owned(), cell));
+}
@@ -16,6 +16,10 @@ can be changed with the --separator flag.
 There is also a condensed view (-c or --condense) that will shorten the
 contents of each field to provide a summary view.
 
+With --number, each record starts with a 'record N' line, where N is the
+index of the record in the input (starting at 0, not counting the header
+row). This remains true when records are skipped with --start.
+
 Usage:
     xsv flatten [options] [<input>]
 
@@ -28,6 +32,13 @@ flatten options:
                            When non-empty, a new line is automatically
                            appended to the separator.
                            [default: #]
+    -N, --number           Write the index of each record before it.
+    --start <n>            Skip the first <n> records. [default: 0]
+    --limit <n>            Only show <n> records.
+    --rtl-safe             End names and fields with right-to-left text,
+                           like Hebrew or Arabic, with an invisible
+                           left-to-right mark, to keep terminals from
+                           mixing up names and values.
 
 Common options:
     -h, --help             Display this message
@@ -36,6 +47,8 @@ Common options:
                            will be its index.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
@@ -43,6 +56,10 @@ struct Args {
     arg_input: Option<String>,
     flag_condense: Option<usize>,
     flag_separator: String,
+    flag_number: bool,
+    flag_start: usize,
+    flag_limit: Option<usize>,
+    flag_rtl_safe: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
 }
@@ -56,22 +73,36 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let headers = rdr.byte_headers()?.clone();
 
     let mut wtr = TabWriter::new(io::stdout());
+    let limit = args.flag_limit.unwrap_or(usize::MAX);
+    let records = rdr.byte_records()
+                     .enumerate()
+                     .skip(args.flag_start)
+                     .take(limit);
     let mut first = true;
-    for r in rdr.byte_records() {
+    for (rowi, r) in records {
         if !first && !args.flag_separator.is_empty() {
             writeln!(&mut wtr, "{}", args.flag_separator)?;
         }
         first = false;
         let r = r?;
+        if args.flag_number {
+            writeln!(&mut wtr, "record {}", rowi)?;
+        }
         for (i, (header, field)) in headers.iter().zip(&r).enumerate() {
+            let mut header = Cow::Borrowed(header);
+            let mut field =
+                util::condense(Cow::Borrowed(&*field), args.flag_condense);
+            if args.flag_rtl_safe {
+                header = util::rtl_safe(header);
+                field = util::rtl_safe(field);
+            }
             if rconfig.no_headers {
                 write!(&mut wtr, "{}", i)?;
             } else {
                 wtr.write_all(&header)?;
             }
             wtr.write_all(b"\t")?;
-            wtr.write_all(&*util::condense(
-                Cow::Borrowed(&*field), args.flag_condense))?;
+            wtr.write_all(&field)?;
             wtr.write_all(b"\n")?;
         }
     }
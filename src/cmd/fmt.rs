@@ -31,6 +31,8 @@ Common options:
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
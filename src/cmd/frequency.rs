@@ -1,3 +1,4 @@
+use std::cmp;
 use std::fs;
 use std::io;
 
@@ -10,7 +11,7 @@ use CliResult;
 use config::{Config, Delimiter};
 use index::Indexed;
 use select::{SelectColumns, Selection};
-use util;
+use util::{self, Progress};
 
 static USAGE: &'static str = "
 Compute a frequency table on CSV data.
@@ -20,8 +21,8 @@ The frequency table is formatted as CSV data:
     field,value,count
 
 By default, there is a row for the N most frequent values for each field in the
-data. The order and number of values can be tweaked with --asc and --limit,
-respectively.
+data. The order and number of values can be tweaked with --sort, --asc, --desc
+and --limit. Values with the same count are always ordered by value.
 
 Since this computes an exact frequency table, memory proportional to the
 cardinality of each column is required.
@@ -34,12 +35,18 @@ frequency options:
                            for. See 'xsv select --help' for the format
                            details. This is provided here because piping 'xsv
                            select' into 'xsv frequency' will disable the use
-                           of indexing.
-    -l, --limit <arg>      Limit the frequency table to the N most common
-                           items. Set to '0' to disable a limit.
-                           [default: 10]
-    -a, --asc              Sort the frequency tables in ascending order by
-                           count. The default is descending order.
+                           of indexing. The tables are still written in the
+                           order of the columns in the data.
+    -l, --limit <arg>      Limit each frequency table to its first N rows,
+                           in the order given by --sort, --asc and --desc.
+                           By default, these are the N most common items.
+                           Set to '0' to disable a limit. [default: 10]
+    --sort <by>            Sort the frequency tables by 'count' or by
+                           'value'. [default: count]
+    -a, --asc              Sort the frequency tables in ascending order.
+                           This is the default when sorting by value.
+    --desc                 Sort the frequency tables in descending order.
+                           This is the default when sorting by count.
     --no-nulls             Don't include NULLs in the frequency table.
     -j, --jobs <arg>       The number of jobs to run in parallel.
                            This works better when the given CSV data has
@@ -48,9 +55,13 @@ frequency options:
                            When set to '0', the number of jobs is set to the
                            number of CPUs detected.
                            [default: 0]
+    --head <n>             Only read the first <n> records of the input.
 
 Common options:
     -h, --help             Display this message
+    -P, --progress         Periodically show the number of records processed
+                           on stderr. This only has an effect when stderr is
+                           a terminal.
     -o, --output <file>    Write output to <file> instead of stdout.
     -n, --no-headers       When set, the first row will NOT be included
                            in the frequency table. Additionally, the 'field'
@@ -58,6 +69,8 @@ Common options:
                            names.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Clone, Deserialize)]
@@ -65,17 +78,29 @@ struct Args {
     arg_input: Option<String>,
     flag_select: SelectColumns,
     flag_limit: usize,
+    flag_sort: SortBy,
     flag_asc: bool,
+    flag_desc: bool,
     flag_no_nulls: bool,
     flag_jobs: usize,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_progress: bool,
+    flag_head: Option<u64>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum SortBy {
+    Count,
+    Value,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
-    let rconfig = args.rconfig();
+    if args.flag_asc && args.flag_desc {
+        return fail!("Only one of --asc and --desc may be given.");
+    }
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let (headers, tables) = match args.rconfig().indexed()? {
@@ -85,14 +110,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     wtr.write_record(vec!["field", "value", "count"])?;
     let head_ftables = headers.into_iter().zip(tables.into_iter());
-    for (i, (header, ftab)) in head_ftables.enumerate() {
-        let mut header = header.to_vec();
-        if rconfig.no_headers {
-            header = (i+1).to_string().into_bytes();
-        }
+    for (header, ftab) in head_ftables {
         for (value, count) in args.counts(&ftab).into_iter() {
             let count = count.to_string();
-            let row = vec![&*header, &*value, count.as_bytes()];
+            let row = vec![header, &*value, count.as_bytes()];
             wtr.write_record(row)?;
         }
     }
@@ -110,14 +131,26 @@ impl Args {
             .delimiter(self.flag_delimiter)
             .no_headers(self.flag_no_headers)
             .select(self.flag_select.clone())
+            .head(self.flag_head)
     }
 
     fn counts(&self, ftab: &FTable) -> Vec<(ByteString, u64)> {
-        let mut counts = if self.flag_asc {
-            ftab.least_frequent()
-        } else {
-            ftab.most_frequent()
-        };
+        let mut counts = ftab.most_frequent();
+        match self.flag_sort {
+            SortBy::Count => {
+                let desc = !self.flag_asc;
+                counts.sort_by(|&(v1, c1), &(v2, c2)| {
+                    let ord = if desc { c2.cmp(&c1) } else { c1.cmp(&c2) };
+                    ord.then_with(|| v1.cmp(v2))
+                });
+            }
+            SortBy::Value => {
+                counts.sort_by(|a, b| a.0.cmp(b.0));
+                if self.flag_desc {
+                    counts.reverse();
+                }
+            }
+        }
         if self.flag_limit > 0 {
             counts = counts.into_iter().take(self.flag_limit).collect();
         }
@@ -131,46 +164,66 @@ impl Args {
     }
 
     fn sequential_ftables(&self) -> CliResult<(Headers, FTables)> {
-        let mut rdr = self.rconfig().reader()?;
+        let rconfig = self.rconfig();
+        let mut rdr = rconfig.reader()?;
         let (headers, sel) = self.sel_headers(&mut rdr)?;
-        Ok((headers, self.ftables(&sel, rdr.byte_records())?))
+        let progress = Progress::new(self.flag_progress, None);
+        let it = rconfig.limit_records(rdr.byte_records());
+        let tables = self.ftables(&sel, it, &progress)?;
+        progress.finish();
+        Ok((headers, tables))
     }
 
     fn parallel_ftables(&self, idx: &mut Indexed<fs::File, fs::File>)
                        -> CliResult<(Headers, FTables)> {
-        let mut rdr = self.rconfig().reader()?;
+        let rconfig = self.rconfig();
+        let mut rdr = rconfig.reader()?;
         let (headers, sel) = self.sel_headers(&mut rdr)?;
 
-        if idx.count() == 0 {
+        let count = rconfig.limit_count(idx.count());
+        if count == 0 {
             return Ok((headers, vec![]));
         }
 
-        let chunk_size = util::chunk_size(idx.count() as usize, self.njobs());
-        let nchunks = util::num_of_chunks(idx.count() as usize, chunk_size);
+        let chunk_size = util::chunk_size(count as usize, self.njobs());
+        let nchunks = util::num_of_chunks(count as usize, chunk_size);
 
+        let progress = Progress::new(self.flag_progress, Some(count));
         let pool = ThreadPool::new(self.njobs());
         let (send, recv) = channel::bounded(0);
         for i in 0..nchunks {
-            let (send, args, sel) = (send.clone(), self.clone(), sel.clone());
+            let (send, args, sel, progress) =
+                (send.clone(), self.clone(), sel.clone(), progress.clone());
             pool.execute(move || {
                 let mut idx = args.rconfig().indexed().unwrap().unwrap();
-                idx.seek((i * chunk_size) as u64).unwrap();
-                let it = idx.byte_records().take(chunk_size);
-                send.send(args.ftables(&sel, it).unwrap());
+                let start = (i * chunk_size) as u64;
+                let len = cmp::min(chunk_size as u64, count - start) as usize;
+                idx.seek(start).unwrap();
+                let it = idx.byte_records().take(len);
+                send.send(args.ftables(&sel, it, &progress).unwrap());
             });
         }
         drop(send);
-        Ok((headers, merge_all(recv).unwrap()))
+        let tables = merge_all(recv).unwrap();
+        progress.finish();
+        Ok((headers, tables))
     }
 
-    fn ftables<I>(&self, sel: &Selection, it: I) -> CliResult<FTables>
-            where I: Iterator<Item=csv::Result<csv::ByteRecord>> {
+    fn ftables<I>(
+        &self,
+        sel: &Selection,
+        it: I,
+        progress: &Progress,
+    ) -> CliResult<FTables>
+    where I: Iterator<Item=csv::Result<csv::ByteRecord>>
+    {
         let null = &b""[..].to_vec();
         let nsel = sel.normal();
         let mut tabs: Vec<_> =
             (0..nsel.len()).map(|_| Frequencies::new()).collect();
         for row in it {
             let row = row?;
+            progress.inc();
             for (i, field) in nsel.select(row.into_iter()).enumerate() {
                 let field = trim(field.to_vec());
                 if !field.is_empty() {
@@ -185,11 +238,25 @@ impl Args {
         Ok(tabs)
     }
 
+    /// Returns the names of the selected columns, in the same order as
+    /// the frequency tables, which follow the order of the columns in the
+    /// data. Without headers, columns are named by their 1-based index.
     fn sel_headers<R: io::Read>(&self, rdr: &mut csv::Reader<R>)
                   -> CliResult<(csv::ByteRecord, Selection)> {
+        let rconfig = self.rconfig();
         let headers = rdr.byte_headers()?;
-        let sel = self.rconfig().selection(headers)?;
-        Ok((sel.select(headers).map(|h| h.to_vec()).collect(), sel))
+        let sel = rconfig.selection(headers)?;
+        let names = sel.normal()
+            .select(headers.iter().enumerate())
+            .map(|(i, h)| {
+                if rconfig.no_headers {
+                    (i + 1).to_string().into_bytes()
+                } else {
+                    h.to_vec()
+                }
+            })
+            .collect();
+        Ok((names, sel))
     }
 
     fn njobs(&self) -> usize {
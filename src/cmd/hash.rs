@@ -0,0 +1,127 @@
+use std::hash::Hasher;
+
+use byteorder::{ByteOrder, LittleEndian};
+use csv;
+
+use CliResult;
+use config::{Config, Delimiter};
+use select::{SelectColumns, Selection};
+use util::{self, FnvHasher};
+
+static USAGE: &'static str = "
+Computes a fingerprint of CSV data, to detect changes.
+
+By default, a single digest of all of the data is printed, including the
+header row. With --rows, a column holding a digest of each row is added
+instead.
+
+Digests are computed from the parsed fields rather than from the raw bytes,
+so data that only differs in its quoting (or in its delimiter) gets the same
+digest. They are still sensitive to the order of rows and columns. Digests
+are 64-bit FNV-1a hashes, written as 16 hexadecimal digits. They are stable
+across platforms, but they are not cryptographic.
+
+  Check whether two files hold the same data:
+  $ xsv hash a.csv && xsv hash b.csv
+
+  Add a digest of the name and email columns to each row:
+  $ xsv hash --rows -s name,email people.csv
+
+Usage:
+    xsv hash [options] [<input>]
+    xsv hash --help
+
+hash options:
+    -s, --select <arg>     Only hash the given columns.
+                           See 'xsv select --help' for the format details.
+    -r, --rows             Append a column with a digest of each row instead
+                           of printing a single digest.
+    -c, --column <name>    The name of the column added by --rows.
+                           [default: hash]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_rows: bool,
+    flag_column: String,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+
+    if !args.flag_rows {
+        let mut hasher = FnvHasher::default();
+        if !rconfig.no_headers {
+            hash_record(&mut hasher, &sel, &headers);
+        }
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            hash_record(&mut hasher, &sel, &record);
+        }
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+        wtr.write_record(&[format!("{:016x}", hasher.finish())])?;
+        return Ok(wtr.flush()?);
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !rconfig.no_headers {
+        let mut headers = headers.clone();
+        headers.push_field(args.flag_column.as_bytes());
+        wtr.write_byte_record(&headers)?;
+    }
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        let mut hasher = FnvHasher::default();
+        hash_record(&mut hasher, &sel, &record);
+        let digest = format!("{:016x}", hasher.finish());
+        record.push_field(digest.as_bytes());
+        wtr.write_byte_record(&record)?;
+    }
+    Ok(wtr.flush()?)
+}
+
+/// Feeds the selected fields of `record` to `hasher`.
+///
+/// Every field is prefixed with its length, and every record with its
+/// number of fields, so that e.g. `ab,c` and `a,bc` hash differently.
+fn hash_record(
+    hasher: &mut FnvHasher,
+    sel: &Selection,
+    record: &csv::ByteRecord,
+) {
+    write_len(hasher, sel.len());
+    for field in sel.select(record) {
+        write_len(hasher, field.len());
+        hasher.write(field);
+    }
+}
+
+/// Feeds `len` to `hasher` as 8 little endian bytes.
+fn write_len(hasher: &mut FnvHasher, len: usize) {
+    let mut buf = [0; 8];
+    LittleEndian::write_u64(&mut buf, len as u64);
+    hasher.write(&buf);
+}
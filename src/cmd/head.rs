@@ -0,0 +1,51 @@
+use CliResult;
+use config::{Config, Delimiter};
+use util;
+
+static USAGE: &'static str = "
+Returns the first records of CSV data, like Unix 'head'.
+
+This is a shortcut for 'xsv slice --len <n>'. The header row is not counted.
+
+Usage:
+    xsv head [options] [<input>]
+    xsv head --help
+
+head options:
+    -l, --len <n>          The number of records to return. [default: 10]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. Otherwise, the first row will always
+                           appear in the output as the header row.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_len: usize,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    rconfig.write_headers(&mut rdr, &mut wtr)?;
+    for r in rdr.byte_records().take(args.flag_len) {
+        wtr.write_byte_record(&r?)?;
+    }
+    Ok(wtr.flush()?)
+}
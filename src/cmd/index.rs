@@ -1,11 +1,14 @@
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 
+use csv;
 use csv_index::RandomAccessSimple;
+use tabwriter::TabWriter;
 
 use CliResult;
 use config::{Config, Delimiter};
+use index::Indexed;
 use util;
 
 static USAGE: &'static str = "
@@ -27,17 +30,30 @@ index options:
                            Generally, this is not currently useful because
                            the only way to use an index is if it is specially
                            named <input>.idx.
+    --check                Verify an existing index instead of creating one.
+                           This checks that the index was not created before
+                           the CSV data was last modified, that it has the
+                           same number of records as the CSV data and that
+                           every indexed position lands on a record boundary.
+                           If the index is stale, an error is reported.
+    --stats                Print the number of records in an existing index
+                           and the size of the index in bytes instead of
+                           creating one.
 
 Common options:
     -h, --help             Display this message
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: String,
     flag_output: Option<String>,
+    flag_check: bool,
+    flag_stats: bool,
     flag_delimiter: Option<Delimiter>,
 }
 
@@ -46,13 +62,80 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let pidx = match args.flag_output {
         None => util::idx_path(&Path::new(&args.arg_input)),
-        Some(p) => PathBuf::from(&p),
+        Some(ref p) => PathBuf::from(p),
     };
 
-    let rconfig = Config::new(&Some(args.arg_input))
-                         .delimiter(args.flag_delimiter);
-    let mut rdr = rconfig.reader_file()?;
-    let mut wtr = io::BufWriter::new(fs::File::create(&pidx)?);
-    RandomAccessSimple::create(&mut rdr, &mut wtr)?;
-    Ok(())
+    match (args.flag_check, args.flag_stats) {
+        (true, true) => fail!("--check and --stats cannot be used together."),
+        (true, false) => args.check_index(&pidx),
+        (false, true) => args.index_stats(&pidx),
+        (false, false) => {
+            let mut rdr = args.rconfig().reader_file()?;
+            let mut wtr = io::BufWriter::new(fs::File::create(&pidx)?);
+            RandomAccessSimple::create(&mut rdr, &mut wtr)?;
+            Ok(())
+        }
+    }
+}
+
+impl Args {
+    fn rconfig(&self) -> Config {
+        Config::new(&Some(self.arg_input.clone()))
+            .delimiter(self.flag_delimiter)
+    }
+
+    fn check_index(&self, pidx: &Path) -> CliResult<()> {
+        let idx_file = open_index(pidx)?;
+        let idx_modified = util::last_modified(&idx_file.metadata()?);
+        let mut idx = RandomAccessSimple::open(idx_file)?;
+
+        // Read every record, including the header row, since the index stores
+        // a position for the header row too.
+        let mut rdr = self.rconfig().no_headers(true).reader_file()?;
+        let data_modified = util::last_modified(&rdr.get_ref().metadata()?);
+        if data_modified > idx_modified {
+            return fail!(format!(
+                "index {} is stale: the CSV data was modified after the \
+                 index was created", pidx.display()));
+        }
+
+        let mut count = 0u64;
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            if count < idx.len() {
+                let want = record.position().expect("position on row").byte();
+                let got = idx.get(count)?.byte();
+                if want != got {
+                    return fail!(format!(
+                        "index {} is stale: record {} starts at byte {} in \
+                         the CSV data, but the index points to byte {}",
+                        pidx.display(), count, want, got));
+                }
+            }
+            count += 1;
+        }
+        if count != idx.len() {
+            return fail!(format!(
+                "index {} is stale: the index has {} records, but the CSV \
+                 data has {} records", pidx.display(), idx.len(), count));
+        }
+        Ok(())
+    }
+
+    fn index_stats(&self, pidx: &Path) -> CliResult<()> {
+        let idx_file = open_index(pidx)?;
+        let size = idx_file.metadata()?.len();
+        let idx = Indexed::open(self.rconfig().reader_file()?, idx_file)?;
+
+        let mut wtr = TabWriter::new(io::stdout());
+        writeln!(&mut wtr, "records\t{}", idx.count())?;
+        writeln!(&mut wtr, "size\t{}", size)?;
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+fn open_index(pidx: &Path) -> CliResult<fs::File> {
+    fs::File::open(pidx).map_err(|err| From::from(format!(
+        "failed to open index {}: {}", pidx.display(), err)))
 }
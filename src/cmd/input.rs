@@ -1,3 +1,5 @@
+use std::io::Read;
+
 use csv;
 
 use CliResult;
@@ -20,12 +22,27 @@ input options:
     --escape <arg>         The escape character to use. When not specified,
                            quotes are escaped by doubling them.
     --no-quoting           Disable quoting completely.
+    --terminator <arg>     The record terminator to use. When not specified,
+                           any of '\\r', '\\n' or '\\r\\n' end a record, which
+                           covers old Mac-style files that only use '\\r'.
+                           Must be a single character.
+    --report               Instead of transforming the CSV data, scan it and
+                           report anomalies that usually indicate malformed
+                           data: records whose number of fields differs from
+                           the first record, and quotes that are not
+                           escaped. The report is written as CSV data with
+                           the columns 'record,line,problem', where 'record'
+                           is the 0-based index of the record (counting the
+                           header row) and 'line' is the line it starts on.
+                           This requires reading all CSV data into memory.
 
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
@@ -36,6 +53,8 @@ struct Args {
     flag_quote: Delimiter,
     flag_escape: Option<Delimiter>,
     flag_no_quoting: bool,
+    flag_terminator: Option<Delimiter>,
+    flag_report: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -52,6 +71,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     if args.flag_no_quoting {
         rconfig = rconfig.quoting(false);
     }
+    if let Some(term) = args.flag_terminator {
+        let term = csv::Terminator::Any(term.as_byte());
+        rconfig = rconfig.read_terminator(term);
+    }
+
+    if args.flag_report {
+        return args.report(rconfig, wconfig);
+    }
 
     let mut rdr = rconfig.reader()?;
     let mut wtr = wconfig.writer()?;
@@ -62,3 +89,135 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     wtr.flush()?;
     Ok(())
 }
+
+impl Args {
+    fn report(&self, rconfig: Config, wconfig: Config) -> CliResult<()> {
+        let mut data = vec![];
+        rconfig.io_reader()?.read_to_end(&mut data)?;
+        // This is the delimiter implied by the file extension, --tsv or
+        // --sniff when --delimiter isn't given.
+        let delimiter = rconfig.read_delimiter();
+
+        let mut rdr = rconfig.flexible(true).from_reader(&*data);
+        let mut wtr = wconfig.writer()?;
+        wtr.write_record(vec!["record", "line", "problem"])?;
+
+        let scanner = QuoteScanner {
+            delimiter: delimiter,
+            quote: self.flag_quote.as_byte(),
+            escape: self.flag_escape.map(|d| d.as_byte()),
+            terminator: self.flag_terminator.map(|d| d.as_byte()),
+        };
+        let mut expected_len = None;
+        let mut row = csv::ByteRecord::new();
+        let mut next = csv::ByteRecord::new();
+        let mut more = rdr.read_byte_record(&mut row)?;
+        while more {
+            let pos = row.position().expect("position on row").clone();
+            more = rdr.read_byte_record(&mut next)?;
+            let end = if more {
+                next.position().expect("position on row").byte() as usize
+            } else {
+                data.len()
+            };
+
+            let mut problems = vec![];
+            match expected_len {
+                None => expected_len = Some(row.len()),
+                Some(len) if len != row.len() => {
+                    problems.push(format!(
+                        "expected {} fields but found {}", len, row.len()));
+                }
+                Some(_) => {}
+            }
+            if !self.flag_no_quoting {
+                let raw = &data[pos.byte() as usize..end];
+                problems.extend(scanner.scan(raw, !more));
+            }
+            for problem in problems {
+                let (record, line) =
+                    (pos.record().to_string(), pos.line().to_string());
+                wtr.write_record(vec![record, line, problem])?;
+            }
+            ::std::mem::swap(&mut row, &mut next);
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// QuoteScanner looks for quotes that aren't escaped in the raw bytes of a
+/// single record.
+///
+/// The CSV parser accepts such quotes without complaint, but they usually
+/// mean that the data was written with different quoting rules than the ones
+/// used to read it.
+struct QuoteScanner {
+    delimiter: u8,
+    quote: u8,
+    escape: Option<u8>,
+    /// The record terminator, or `None` if any of `\r` and `\n` is one.
+    terminator: Option<u8>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ScanState {
+    StartField,
+    Unquoted,
+    Quoted,
+    QuotedEscape,
+    QuoteInQuoted,
+}
+
+impl QuoteScanner {
+    fn scan(&self, raw: &[u8], last: bool) -> Vec<String> {
+        use self::ScanState::*;
+
+        let double_quote = self.escape.is_none();
+        let mut problems = vec![];
+        let mut field = 1;
+        let mut bad_field = 0;
+        let mut state = StartField;
+        for &b in raw {
+            let is_term = match self.terminator {
+                None => b == b'\n' || b == b'\r',
+                Some(term) => b == term,
+            };
+            let mut bad = false;
+            state = match state {
+                StartField | Unquoted if b == self.delimiter => {
+                    field += 1;
+                    StartField
+                }
+                StartField | Unquoted if is_term => StartField,
+                StartField if b == self.quote => Quoted,
+                StartField => Unquoted,
+                Unquoted => {
+                    bad = b == self.quote;
+                    Unquoted
+                }
+                Quoted if Some(b) == self.escape => QuotedEscape,
+                Quoted if b == self.quote => QuoteInQuoted,
+                Quoted | QuotedEscape => Quoted,
+                QuoteInQuoted if b == self.quote && double_quote => Quoted,
+                QuoteInQuoted if b == self.delimiter => {
+                    field += 1;
+                    StartField
+                }
+                QuoteInQuoted if is_term => StartField,
+                QuoteInQuoted => {
+                    bad = true;
+                    Unquoted
+                }
+            };
+            if bad && bad_field != field {
+                bad_field = field;
+                problems.push(format!("unescaped quote in field {}", field));
+            }
+        }
+        if last && (state == Quoted || state == QuotedEscape) {
+            problems.push(format!("unclosed quote in field {}", field));
+        }
+        problems
+    }
+}
@@ -2,14 +2,15 @@ use std::collections::hash_map::{HashMap, Entry};
 use std::fmt;
 use std::fs;
 use std::io;
-use std::iter::repeat;
+use std::iter::{once, repeat};
 use std::str;
 
 use byteorder::{WriteBytesExt, BigEndian};
 use csv;
+use strsim;
 
 use CliResult;
-use config::{Config, Delimiter};
+use config::{self, Config, Delimiter};
 use index::Indexed;
 use select::{SelectColumns, Selection};
 use util;
@@ -56,6 +57,16 @@ join options:
                            data sets given. The number of rows return is
                            equal to N * M, where N and M correspond to the
                            number of rows in the given data sets, respectively.
+    --fuzzy <threshold>    Do an inner join where keys only need to be
+                           similar instead of equal. The similarity of two
+                           keys is the lowest Jaro-Winkler similarity of
+                           their columns, between 0 (nothing in common) and
+                           1 (equal). Keys match when their similarity is
+                           at least <threshold>, which is written in an
+                           extra 'match_score' column. This compares every
+                           row in the first data set with every distinct key
+                           in the second data set, so it takes O(N * M)
+                           time.
     --nulls                When set, joins will work on empty fields.
                            Otherwise, empty fields are completely ignored.
                            (In fact, any row that has an empty field in the
@@ -69,6 +80,8 @@ Common options:
                            sliced, etc.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 type ByteString = Vec<u8>;
@@ -83,6 +96,7 @@ struct Args {
     flag_right: bool,
     flag_full: bool,
     flag_cross: bool,
+    flag_fuzzy: Option<f64>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_no_case: bool,
@@ -93,6 +107,17 @@ struct Args {
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let mut state = args.new_io_state()?;
+    if let Some(threshold) = args.flag_fuzzy {
+        if args.flag_left || args.flag_right
+                || args.flag_full || args.flag_cross {
+            return fail!("--fuzzy cannot be used with other join \
+                          operations.");
+        }
+        if threshold.is_nan() || threshold < 0.0 || threshold > 1.0 {
+            return fail!("--fuzzy must be between 0 and 1.");
+        }
+        return state.fuzzy_join(threshold);
+    }
     match (
         args.flag_left,
         args.flag_right,
@@ -124,7 +149,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 }
 
 struct IoState<R, W: io::Write> {
-    wtr: csv::Writer<W>,
+    wtr: config::Writer<W>,
     rdr1: csv::Reader<R>,
     sel1: Selection,
     rdr2: csv::Reader<R>,
@@ -167,6 +192,51 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
         Ok(())
     }
 
+    fn fuzzy_join(mut self, threshold: f64) -> CliResult<()> {
+        if !self.no_headers {
+            let mut headers = self.rdr1.byte_headers()?.clone();
+            headers.extend(self.rdr2.byte_headers()?.iter());
+            headers.push_field(b"match_score");
+            self.wtr.write_record(&headers)?;
+        }
+
+        let mut scratch = csv::ByteRecord::new();
+        let mut validx = ValueIndex::new(
+            self.rdr2, &self.sel2, self.casei, self.nulls)?;
+        // Keys are compared in the order in which they first appear in the
+        // second data set, so that the output doesn't depend on hashing.
+        let mut keys: Vec<(Vec<String>, Vec<usize>)> =
+            validx.values.iter()
+                  .map(|(key, rows)| (lossy_key(key), rows.clone()))
+                  .collect();
+        keys.sort_by_key(|key| key.1[0]);
+
+        for row in self.rdr1.byte_records() {
+            let row = row?;
+            let key = get_row_key(&self.sel1, &row, self.casei);
+            if !self.nulls && key.iter().any(|f| f.is_empty()) {
+                continue;
+            }
+            let key = lossy_key(&key);
+            for (key2, rows) in &keys {
+                let score = similarity(&key, key2);
+                if score < threshold {
+                    continue;
+                }
+                let score = format!("{:.4}", score);
+                for &rowi in rows {
+                    validx.idx.seek(rowi as u64)?;
+                    validx.idx.read_byte_record(&mut scratch)?;
+                    let combined = row.iter()
+                                      .chain(scratch.iter())
+                                      .chain(once(score.as_bytes()));
+                    self.wtr.write_record(combined)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn outer_join(mut self, right: bool) -> CliResult<()> {
         if right {
             ::std::mem::swap(&mut self.rdr1, &mut self.rdr2);
@@ -323,15 +393,15 @@ impl Args {
     }
 }
 
-struct ValueIndex<R> {
+pub struct ValueIndex<R> {
     // This maps tuples of values to corresponding rows.
-    values: HashMap<Vec<ByteString>, Vec<usize>>,
-    idx: Indexed<R, io::Cursor<Vec<u8>>>,
-    num_rows: usize,
+    pub values: HashMap<Vec<ByteString>, Vec<usize>>,
+    pub idx: Indexed<R, io::Cursor<Vec<u8>>>,
+    pub num_rows: usize,
 }
 
 impl<R: io::Read + io::Seek> ValueIndex<R> {
-    fn new(
+    pub fn new(
         mut rdr: csv::Reader<R>,
         sel: &Selection,
         casei: bool,
@@ -411,7 +481,7 @@ impl<R> fmt::Debug for ValueIndex<R> {
     }
 }
 
-fn get_row_key(
+pub fn get_row_key(
     sel: &Selection,
     row: &csv::ByteRecord,
     casei: bool,
@@ -419,6 +489,18 @@ fn get_row_key(
     sel.select(row).map(|v| transform(&v, casei)).collect()
 }
 
+fn lossy_key(key: &[ByteString]) -> Vec<String> {
+    key.iter().map(|f| String::from_utf8_lossy(f).into_owned()).collect()
+}
+
+/// Returns the lowest Jaro-Winkler similarity of the columns of two keys.
+fn similarity(key1: &[String], key2: &[String]) -> f64 {
+    key1.iter()
+        .zip(key2)
+        .map(|(a, b)| strsim::jaro_winkler(a, b))
+        .fold(1.0, f64::min)
+}
+
 fn transform(bs: &[u8], casei: bool) -> ByteString {
     match str::from_utf8(bs) {
         Err(_) => bs.to_vec(),
@@ -1,20 +1,31 @@
 pub mod cat;
 pub mod count;
+pub mod describe;
+pub mod diff;
+pub mod explode;
 pub mod fixlengths;
 pub mod flatten;
 pub mod fmt;
 pub mod frequency;
+pub mod hash;
+pub mod head;
 pub mod headers;
 pub mod index;
 pub mod input;
 pub mod join;
 pub mod partition;
+pub mod pivot;
 pub mod reverse;
 pub mod sample;
+pub mod schema;
 pub mod search;
 pub mod select;
 pub mod slice;
 pub mod sort;
+pub mod sparkline;
 pub mod split;
 pub mod stats;
 pub mod table;
+pub mod tail;
+pub mod uniq;
+pub mod unpivot;
@@ -8,7 +8,7 @@ use csv;
 use regex::Regex;
 
 use CliResult;
-use config::{Config, Delimiter};
+use config::{self, Config, Delimiter};
 use select::SelectColumns;
 use util::{self, FilenameTemplate};
 
@@ -18,7 +18,14 @@ Partitions the given CSV data into chunks based on the value of a column
 The files are written to the output directory with filenames based on the
 values in the partition column and the `--filename` flag.
 
+To partition on a combination of columns, give them with '--select' instead
+of <column>. Every distinct combination of values gets its own file, named
+after the sanitized values joined by '--separator'.
+
+  $ xsv partition --select state,city outdir data.csv
+
 Usage:
+    xsv partition [options] --select <cols> <outdir> [<input>]
     xsv partition [options] <column> <outdir> [<input>]
     xsv partition --help
 
@@ -28,10 +35,21 @@ partition options:
                            will be replaced by a value based on the value
                            of the field, but sanitized for shell safety.
                            [default: {}.csv]
+    -s, --select <cols>    Partition on the combination of these columns.
+                           See 'xsv select --help' for the format details.
+    --separator <sep>      The string joining the values of the '--select'
+                           columns in filenames. [default: _]
     -p, --prefix-length <n>  Truncate the partition column after the
                            specified number of bytes when creating the
-                           output file.
-    --drop                 Drop the partition column from results.
+                           output file. With '--select', each value is
+                           truncated separately.
+    --drop                 Drop the partition columns from results.
+    -b, --buckets <n>      Instead of writing one file per distinct value,
+                           hash the value of the partition column and write
+                           each row to one of <n> files, named 'bucket_0'
+                           through 'bucket_<n-1>' according to '--filename'.
+                           Rows with the same value always land in the same
+                           bucket, regardless of the input or platform.
 
 Common options:
     -h, --help             Display this message
@@ -40,22 +58,34 @@ Common options:
                            appear in all chunks as the header row.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Clone, Deserialize)]
 struct Args {
-    arg_column: SelectColumns,
+    arg_column: Option<SelectColumns>,
     arg_input: Option<String>,
     arg_outdir: String,
     flag_filename: FilenameTemplate,
+    flag_select: Option<SelectColumns>,
+    flag_separator: String,
     flag_prefix_length: Option<usize>,
     flag_drop: bool,
+    flag_buckets: Option<u64>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_buckets == Some(0) {
+        return fail!("--buckets must be greater than 0.");
+    }
+    if args.flag_separator.contains('/')
+            || args.flag_separator.contains('\\') {
+        return fail!("--separator must not contain a path separator.");
+    }
     fs::create_dir_all(&args.arg_outdir)?;
 
     // It would be nice to support efficient parallel partitions, but doing
@@ -71,21 +101,28 @@ impl Args {
         Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
             .no_headers(self.flag_no_headers)
-            .select(self.arg_column.clone())
+            .select(self.key_select())
+    }
+
+    /// The columns given by either `--select` or `<column>`.
+    fn key_select(&self) -> SelectColumns {
+        self.flag_select.clone()
+            .or_else(|| self.arg_column.clone())
+            .unwrap_or_default()
     }
 
-    /// Get the column to use as a key.
-    fn key_column(
+    /// Get the columns to use as a key.
+    fn key_columns(
         &self,
         rconfig: &Config,
         headers: &csv::ByteRecord,
-    ) -> CliResult<usize> {
+    ) -> CliResult<Vec<usize>> {
         let select_cols = rconfig.selection(headers)?;
-        if select_cols.len() == 1 {
-            Ok(select_cols[0])
-        } else {
-            fail!("can only partition on one column")
+        if self.flag_select.is_none() && select_cols.len() != 1 {
+            return fail!("can only partition on one column \
+                          (use --select for several)");
         }
+        Ok(select_cols.to_vec())
     }
 
     /// A basic sequential partition.
@@ -93,30 +130,45 @@ impl Args {
         let rconfig = self.rconfig();
         let mut rdr = rconfig.reader()?;
         let headers = rdr.byte_headers()?.clone();
-        let key_col = self.key_column(&rconfig, &headers)?;
-        let mut gen = WriterGenerator::new(self.flag_filename.clone());
+        let key_cols = self.key_columns(&rconfig, &headers)?;
+        let mut gen = WriterGenerator::new(
+            self.flag_filename.clone(), self.flag_separator.clone());
 
-        let mut writers: HashMap<Vec<u8>, BoxedWriter> =
+        let mut writers: HashMap<Vec<Vec<u8>>, BoxedWriter> =
             HashMap::new();
         let mut row = csv::ByteRecord::new();
         while rdr.read_byte_record(&mut row)? {
             // Decide what file to put this in.
-            let column = &row[key_col];
-            let key = match self.flag_prefix_length {
-                // We exceed --prefix-length, so ignore the extra bytes.
-                Some(len) if len < column.len() => &column[0..len],
-                _ => &column[..],
+            let key: Vec<Vec<u8>> = key_cols.iter().map(|&i| {
+                let column = &row[i];
+                match self.flag_prefix_length {
+                    // We exceed --prefix-length, so ignore the extra bytes.
+                    Some(len) if len < column.len() => {
+                        column[0..len].to_vec()
+                    }
+                    _ => column.to_vec(),
+                }
+            }).collect();
+            let key = match self.flag_buckets {
+                None => key,
+                Some(n) => {
+                    let joined =
+                        key.join(self.flag_separator.as_bytes());
+                    let hash = util::fnv_hash(&joined) % n;
+                    vec![format!("bucket_{}", hash).into_bytes()]
+                }
             };
-            let mut entry = writers.entry(key.to_vec());
+            let mut entry = writers.entry(key);
             let wtr = match entry {
                 Entry::Occupied(ref mut occupied) => occupied.get_mut(),
                 Entry::Vacant(vacant) => {
                     // We have a new key, so make a new writer.
-                    let mut wtr = gen.writer(&*self.arg_outdir, key)?;
+                    let mut wtr =
+                        gen.writer(&*self.arg_outdir, vacant.key())?;
                     if !rconfig.no_headers {
                         if self.flag_drop {
                             wtr.write_record(headers.iter().enumerate()
-                                .filter_map(|(i, e)| if i != key_col { Some(e) } else { None }))?;
+                                .filter_map(|(i, e)| if !key_cols.contains(&i) { Some(e) } else { None }))?;
                         } else {
                             wtr.write_record(&headers)?;
                         }
@@ -126,7 +178,7 @@ impl Args {
             };
             if self.flag_drop {
                 wtr.write_record(row.iter().enumerate()
-                    .filter_map(|(i, e)| if i != key_col { Some(e) } else { None }))?;
+                    .filter_map(|(i, e)| if !key_cols.contains(&i) { Some(e) } else { None }))?;
             } else {
                 wtr.write_byte_record(&row)?;
             }
@@ -135,20 +187,22 @@ impl Args {
     }
 }
 
-type BoxedWriter = csv::Writer<Box<io::Write+'static>>;
+type BoxedWriter = config::Writer<Box<io::Write+'static>>;
 
 /// Generates unique filenames based on CSV values.
 struct WriterGenerator {
     template: FilenameTemplate,
+    separator: String,
     counter: usize,
     used: HashSet<String>,
     non_word_char: Regex,
 }
 
 impl WriterGenerator {
-    fn new(template: FilenameTemplate) -> WriterGenerator {
+    fn new(template: FilenameTemplate, separator: String) -> WriterGenerator {
         WriterGenerator {
             template: template,
+            separator: separator,
             counter: 1,
             used: HashSet::new(),
             non_word_char: Regex::new(r"\W").unwrap(),
@@ -156,7 +210,11 @@ impl WriterGenerator {
     }
 
     /// Create a CSV writer for `key`.  Does not add headers.
-    fn writer<P>(&mut self, path: P, key: &[u8]) -> io::Result<BoxedWriter>
+    fn writer<P>(
+        &mut self,
+        path: P,
+        key: &[Vec<u8>],
+    ) -> io::Result<BoxedWriter>
         where P: AsRef<Path>
     {
         let unique_value = self.unique_value(key);
@@ -166,16 +224,18 @@ impl WriterGenerator {
     /// Generate a unique value for `key`, suitable for use in a
     /// "shell-safe" filename.  If you pass `key` twice, you'll get two
     /// different values.
-    fn unique_value(&mut self, key: &[u8]) -> String {
-        // Sanitize our key.
-        let utf8 = String::from_utf8_lossy(key);
-        let safe = self.non_word_char.replace_all(&*utf8, "").into_owned();
-        let base =
+    fn unique_value(&mut self, key: &[Vec<u8>]) -> String {
+        // Sanitize each part of our key, then join them.
+        let parts: Vec<String> = key.iter().map(|part| {
+            let utf8 = String::from_utf8_lossy(part);
+            let safe = self.non_word_char.replace_all(&*utf8, "");
             if safe.is_empty() {
                 "empty".to_owned()
             } else {
-                safe
-            };
+                safe.into_owned()
+            }
+        }).collect();
+        let base = parts.join(&self.separator);
 
         // Now check for collisions.
         if !self.used.contains(&base) {
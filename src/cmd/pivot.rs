@@ -0,0 +1,251 @@
+use std::collections::hash_map::{HashMap, Entry};
+
+use csv;
+
+use CliResult;
+use config::{Config, Delimiter};
+use select::SelectColumns;
+use util;
+
+static USAGE: &'static str = "
+Pivots CSV data from a long format into a wide format.
+
+One row is written for each distinct combination of values in the --index
+columns. Each distinct value of the --columns column becomes a new column,
+whose cells are taken from the --values column. Rows and new columns are
+written in the order in which they first appear in the input. Cells that have
+no corresponding value are left empty.
+
+For example, given this data:
+
+    city,year,population
+    Boston,2000,589
+    Boston,2010,617
+    Paris,2010,2243
+
+running 'xsv pivot --index city --columns year --values population' gives:
+
+    city,2000,2010
+    Boston,589,617
+    Paris,,2243
+
+When more than one record maps to the same cell, the --agg option decides
+which value is kept: 'first' keeps the first value seen while 'sum' adds all
+values together (in which case every value must be a number).
+
+This command requires reading all CSV data into memory.
+
+Usage:
+    xsv pivot [options] --index <cols> --columns <col> --values <col> [<input>]
+    xsv pivot --help
+
+pivot options:
+    --index <cols>         The columns identifying each output row.
+                           See 'xsv select --help' for the format details.
+    --columns <col>        The column whose distinct values become the new
+                           columns.
+    --values <col>         The column containing the values of the new
+                           columns.
+    --agg <agg>            How to combine several values for the same cell.
+                           Either 'first' or 'sum'. [default: first]
+    --max-groups <n>       Fail as soon as there are more than <n> distinct
+                           combinations of values in the --index columns.
+                           This guards against running out of memory when
+                           the --index columns have too many values.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. A header row is still written, where
+                           the --index columns are named after their index
+                           (starting at 1) and the new columns after the
+                           pivot values.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_index: SelectColumns,
+    flag_columns: SelectColumns,
+    flag_values: SelectColumns,
+    flag_agg: Agg,
+    flag_max_groups: Option<usize>,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum Agg {
+    First,
+    Sum,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let use_names = !args.flag_no_headers;
+    let index_sel = args.flag_index.selection(&headers, use_names)?;
+    let column = single_column(&args.flag_columns, &headers, use_names,
+                               "--columns")?;
+    let value = single_column(&args.flag_values, &headers, use_names,
+                              "--values")?;
+
+    let mut pivot = Pivot::new(args.flag_agg, args.flag_max_groups);
+    let mut row = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut row)? {
+        let key = index_sel.select(&row).map(|f| f.to_vec()).collect();
+        pivot.add(key, &row[column], &row[value])?;
+    }
+
+    let mut record = csv::ByteRecord::new();
+    if use_names {
+        record.extend(index_sel.select(&headers));
+    } else {
+        for &i in index_sel.iter() {
+            record.push_field((i + 1).to_string().as_bytes());
+        }
+    }
+    record.extend(&pivot.columns);
+    wtr.write_byte_record(&record)?;
+    let ncols = pivot.columns.len();
+    for (key, cells) in pivot.rows {
+        let mut record = csv::ByteRecord::new();
+        record.extend(&key);
+        for i in 0..ncols {
+            match cells.get(&i) {
+                None => record.push_field(b""),
+                Some(cell) => record.push_field(&cell.to_bytes()),
+            }
+        }
+        wtr.write_byte_record(&record)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Returns the index of the column selected by `sel`, which must select
+/// exactly one column.
+fn single_column(
+    sel: &SelectColumns,
+    headers: &csv::ByteRecord,
+    use_names: bool,
+    flag: &str,
+) -> CliResult<usize> {
+    let sel = sel.selection(headers, use_names)?;
+    if sel.len() != 1 {
+        return fail!(format!("{} must select exactly one column, but it \
+                              selected {}.", flag, sel.len()));
+    }
+    Ok(sel[0])
+}
+
+/// The values of the --index columns for one output row.
+type Key = Vec<Vec<u8>>;
+
+/// Pivot accumulates the cells of the wide table.
+///
+/// Rows and columns are kept in the order in which they are first seen.
+struct Pivot {
+    agg: Agg,
+    max_groups: Option<usize>,
+    columns: Vec<Vec<u8>>,
+    column_idx: HashMap<Vec<u8>, usize>,
+    rows: Vec<(Key, HashMap<usize, Cell>)>,
+    row_idx: HashMap<Key, usize>,
+}
+
+enum Cell {
+    First(Vec<u8>),
+    Sum(f64),
+}
+
+impl Pivot {
+    fn new(agg: Agg, max_groups: Option<usize>) -> Pivot {
+        Pivot {
+            agg: agg,
+            max_groups: max_groups,
+            columns: vec![],
+            column_idx: HashMap::new(),
+            rows: vec![],
+            row_idx: HashMap::new(),
+        }
+    }
+
+    fn add(
+        &mut self,
+        key: Key,
+        column: &[u8],
+        value: &[u8],
+    ) -> CliResult<()> {
+        let col = match self.column_idx.entry(column.to_vec()) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e) => {
+                self.columns.push(column.to_vec());
+                *e.insert(self.columns.len() - 1)
+            }
+        };
+        let row = match self.row_idx.entry(key) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e) => {
+                if let Some(max) = self.max_groups {
+                    if self.rows.len() >= max {
+                        return fail!(format!(
+                            "There are more than {} distinct combinations \
+                             of values in the --index columns (see \
+                             --max-groups).", max));
+                    }
+                }
+                self.rows.push((e.key().clone(), HashMap::new()));
+                *e.insert(self.rows.len() - 1)
+            }
+        };
+        let cells = &mut self.rows[row].1;
+        match self.agg {
+            Agg::First => {
+                cells.entry(col)
+                     .or_insert_with(|| Cell::First(value.to_vec()));
+            }
+            // Empty values are treated as missing rather than as zero.
+            Agg::Sum if value.is_empty() => {}
+            Agg::Sum => {
+                let n = parse_number(value)?;
+                match *cells.entry(col).or_insert(Cell::Sum(0.0)) {
+                    Cell::Sum(ref mut sum) => *sum += n,
+                    Cell::First(_) => unreachable!(),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Cell {
+    fn to_bytes(&self) -> Vec<u8> {
+        match *self {
+            Cell::First(ref value) => value.clone(),
+            Cell::Sum(sum) => sum.to_string().into_bytes(),
+        }
+    }
+}
+
+fn parse_number(value: &[u8]) -> CliResult<f64> {
+    let s = String::from_utf8_lossy(value);
+    match s.trim().parse::<f64>() {
+        Ok(n) => Ok(n),
+        Err(_) => fail!(format!("Cannot sum '{}' because it is not a \
+                                 number.", s)),
+    }
+}
@@ -1,3 +1,5 @@
+use csv;
+
 use CliResult;
 use config::{Config, Delimiter};
 use util;
@@ -10,9 +12,16 @@ or when keys are not unique and order of rows with the same key needs to be pres
 
 Note that this requires reading all of the CSV data into memory.
 
+With --columns, the order of the columns of every row (including the header
+row) is reversed instead, while the rows stay in order. This doesn't require
+reading all of the CSV data into memory.
+
 Usage:
     xsv reverse [options] [<input>]
 
+reverse options:
+    -c, --columns          Reverse the order of columns instead of rows.
+
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
@@ -22,11 +31,14 @@ Common options:
                            appear as the header row in the output.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
+    flag_columns: bool,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
@@ -40,6 +52,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let mut rdr = rconfig.reader()?;
 
+    if args.flag_columns {
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+        if !rconfig.no_headers {
+            wtr.write_record(rdr.byte_headers()?.iter().rev())?;
+        }
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            wtr.write_record(record.iter().rev())?;
+        }
+        return Ok(wtr.flush()?);
+    }
+
     let mut all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
     all.reverse();
 
@@ -1,9 +1,7 @@
 use std::io;
 
-use byteorder::{ByteOrder, LittleEndian};
 use csv;
-use rand::{self, Rng, SeedableRng};
-use rand::rngs::StdRng;
+use rand::Rng;
 
 use CliResult;
 use config::{Config, Delimiter};
@@ -41,6 +39,8 @@ Common options:
                            in the output.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
@@ -115,17 +115,7 @@ fn sample_reservoir<R: io::Read>(
         reservoir.push(row?);
     }
 
-    // Seeding rng
-    let mut rng: StdRng = match seed {
-        None => {
-            StdRng::from_rng(rand::thread_rng()).unwrap()
-        }
-        Some(seed) => {
-            let mut buf = [0u8; 32];
-            LittleEndian::write_u64(&mut buf, seed as u64);
-            SeedableRng::from_seed(buf)
-        }
-    };
+    let mut rng = util::acquire_rng(seed);
 
     // Now do the sampling.
     for (i, row) in records {
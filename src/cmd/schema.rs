@@ -0,0 +1,195 @@
+use std::str;
+
+use stats::Commute;
+
+use CliResult;
+use cmd::stats::FieldType;
+use config::{Config, Delimiter};
+use select::SelectColumns;
+use util;
+
+static USAGE: &'static str = "
+Infers a schema for CSV data.
+
+One row is written for each column with the following fields: the column
+name, its inferred type, whether it contains empty values and a few example
+values. The inferred type is one of 'integer', 'float', 'boolean', 'date',
+'string' or 'null' (when every value is empty). Empty values never affect the
+inferred type. A column is a 'boolean' when every value is 'true' or 'false'
+(ignoring case) and a 'date' when every value looks like 'YYYY-MM-DD',
+optionally followed by a time such as 'THH:MM' or ' HH:MM:SS'.
+
+Inferring a schema reads every record by default. Use --sample to only look at
+the first few records instead.
+
+Usage:
+    xsv schema [options] [<input>]
+
+schema options:
+    -s, --select <arg>     Select a subset of columns to infer a schema for.
+                           See 'xsv select --help' for the format details.
+    --sample <n>           Only infer the schema from the first <n> records.
+    --examples <n>         The number of distinct example values to show for
+                           each column. [default: 3]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will NOT be interpreted
+                           as column names. i.e., It will be included in the
+                           schema inference.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_sample: Option<u64>,
+    flag_examples: usize,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select)
+        .head(args.flag_sample);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+
+    let mut columns: Vec<Column> =
+        (0..sel.len()).map(|_| Column::default()).collect();
+    for row in rconfig.limit_records(rdr.byte_records()) {
+        let row = row?;
+        for (col, field) in columns.iter_mut().zip(sel.select(&row)) {
+            col.add(field, args.flag_examples);
+        }
+    }
+
+    wtr.write_record(&["field", "type", "nullable", "examples"])?;
+    let names = sel.iter().zip(sel.select(&headers));
+    for ((&i, header), col) in names.zip(columns) {
+        let header =
+            if args.flag_no_headers {
+                (i + 1).to_string().into_bytes()
+            } else {
+                header.to_vec()
+            };
+        let nullable = if col.nullable { "true" } else { "false" };
+        wtr.write_record(vec![
+            &*header,
+            col.type_name().as_bytes(),
+            nullable.as_bytes(),
+            &*col.examples.join(&b", "[..]),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Column accumulates what is known about the type of a column.
+struct Column {
+    typ: FieldType,
+    boolean: bool,
+    date: bool,
+    nullable: bool,
+    examples: Vec<Vec<u8>>,
+}
+
+impl Default for Column {
+    fn default() -> Column {
+        Column {
+            typ: FieldType::default(),
+            boolean: true,
+            date: true,
+            nullable: false,
+            examples: vec![],
+        }
+    }
+}
+
+impl Column {
+    fn add(&mut self, sample: &[u8], max_examples: usize) {
+        if sample.is_empty() {
+            self.nullable = true;
+            return;
+        }
+        self.typ.merge(FieldType::from_sample(sample));
+        if self.boolean {
+            self.boolean = is_boolean(sample);
+        }
+        if self.date {
+            self.date = str::from_utf8(sample).map(is_date).unwrap_or(false);
+        }
+        if self.examples.len() < max_examples
+                && !self.examples.iter().any(|e| &**e == sample) {
+            self.examples.push(sample.to_vec());
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self.typ {
+            FieldType::TNull => "null",
+            _ if self.boolean => "boolean",
+            _ if self.date => "date",
+            FieldType::TInteger => "integer",
+            FieldType::TFloat => "float",
+            FieldType::TUnicode => "string",
+            FieldType::TUnknown => "unknown",
+        }
+    }
+}
+
+fn is_boolean(sample: &[u8]) -> bool {
+    sample.eq_ignore_ascii_case(b"true")
+        || sample.eq_ignore_ascii_case(b"false")
+}
+
+/// Returns true if `s` looks like `YYYY-MM-DD`, optionally followed by a
+/// time of the form `HH:MM` or `HH:MM:SS` separated by `T` or a space.
+fn is_date(s: &str) -> bool {
+    fn num(s: &[u8], len: usize, max: u32) -> Option<u32> {
+        if s.len() < len || !s[..len].iter().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let n = s[..len].iter().fold(0, |n, &b| n * 10 + (b - b'0') as u32);
+        if n > max { None } else { Some(n) }
+    }
+
+    let b = s.as_bytes();
+    if b.len() < 10 || b[4] != b'-' || b[7] != b'-' {
+        return false;
+    }
+    match (num(b, 4, 9999), num(&b[5..], 2, 12), num(&b[8..], 2, 31)) {
+        (Some(_), Some(m), Some(d)) if m >= 1 && d >= 1 => {}
+        _ => return false,
+    }
+    let time = &b[10..];
+    if time.is_empty() {
+        return true;
+    }
+    if time[0] != b'T' && time[0] != b' ' {
+        return false;
+    }
+    let time = &time[1..];
+    let hm = time.len() >= 5
+        && num(time, 2, 23).is_some()
+        && time[2] == b':'
+        && num(&time[3..], 2, 59).is_some();
+    match time.len() {
+        5 => hm,
+        8 => hm && time[5] == b':' && num(&time[6..], 2, 60).is_some(),
+        _ => false,
+    }
+}
@@ -1,9 +1,12 @@
+use std::collections::VecDeque;
+use std::io::{self, Write};
+
 use csv;
 use regex::bytes::RegexBuilder;
 
 use CliResult;
 use config::{Config, Delimiter};
-use select::SelectColumns;
+use select::{SelectColumns, Selection};
 use util;
 
 static USAGE: &'static str = "
@@ -14,6 +17,12 @@ then the row is written to the output. The columns to search can be limited
 with the '--select' flag (but the full row is still written to the output if
 there is a match).
 
+Like 'grep -C', the '--context' flag also writes the rows around each match.
+Rows are never written twice, even when the context of two matches overlaps.
+
+With '--count-column', rows aren't filtered at all. Instead, every row gets
+a new column with the number of matches of the regex in its selected fields.
+
 Usage:
     xsv search [options] <regex> [<input>]
     xsv search --help
@@ -24,6 +33,19 @@ search options:
     -s, --select <arg>     Select the columns to search. See 'xsv select -h'
                            for the full syntax.
     -v, --invert-match     Select only rows that did not match
+    -C, --context <n>      Also write the <n> rows before and after each
+                           matching row. [default: 0]
+    --group-separator <sep>  Write a row whose first field is <sep>, and
+                           whose other fields are empty, between groups of
+                           rows that aren't adjacent in the input. With
+                           '--raw', <sep> is written on a line of its own.
+    --no-header-if-empty   Don't write the header row when no row matches,
+                           so that the output is empty.
+    --count-column <name>  Write all rows, with an extra column named <name>
+                           holding the number of matches in each row.
+    --raw                  Write rows exactly as they appear in the input
+                           instead of rewriting them. This preserves their
+                           quoting, delimiter and line terminator.
 
 Common options:
     -h, --help             Display this message
@@ -33,6 +55,8 @@ Common options:
                            sliced, etc.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
@@ -44,7 +68,12 @@ struct Args {
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
     flag_invert_match: bool,
+    flag_context: usize,
+    flag_group_separator: Option<String>,
+    flag_no_header_if_empty: bool,
+    flag_count_column: Option<String>,
     flag_ignore_case: bool,
+    flag_raw: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -57,24 +86,165 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
+    if let Some(ref name) = args.flag_count_column {
+        if args.flag_raw || args.flag_invert_match || args.flag_context > 0 {
+            return fail!("--count-column cannot be used with --raw, \
+                          --invert-match or --context.");
+        }
+        let mut rdr = rconfig.reader()?;
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+
+        let mut headers = rdr.byte_headers()?.clone();
+        let sel = rconfig.selection(&headers)?;
+        if !rconfig.no_headers {
+            headers.push_field(name.as_bytes());
+            wtr.write_record(&headers)?;
+        }
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            let count: usize = sel.select(&record)
+                .map(|f| pattern.find_iter(f).count())
+                .sum();
+            record.push_field(count.to_string().as_bytes());
+            wtr.write_byte_record(&record)?;
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    let invert = args.flag_invert_match;
+    let is_match = |sel: &Selection, record: &csv::ByteRecord| {
+        let m = sel.select(record).any(|f| pattern.is_match(f));
+        m != invert
+    };
+
+    if args.flag_raw {
+        let mut rdr = rconfig.raw_reader()?;
+        let wconfig = Config::new(&args.flag_output);
+        let mut wtr = io::BufWriter::new(wconfig.io_writer()?);
+
+        let headers = rdr.byte_headers()?;
+        let sel = rconfig.selection(&headers)?;
+
+        // The header row is only written along with the first row when
+        // --no-header-if-empty is given.
+        let mut raw_headers = Some(rdr.raw().to_vec());
+        if !args.flag_no_header_if_empty {
+            wtr.write_all(rdr.raw())?;
+            raw_headers = None;
+        }
+        let mut context = Context::new(args.flag_context);
+        let mut rows = vec![];
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            let matched = is_match(&sel, &record);
+            let gap = context.add(matched, || rdr.raw().to_vec(), &mut rows);
+            if !rows.is_empty() {
+                if let Some(raw_headers) = raw_headers.take() {
+                    wtr.write_all(&raw_headers)?;
+                }
+            }
+            if let (true, Some(sep)) = (gap, &args.flag_group_separator) {
+                wtr.write_all(sep.as_bytes())?;
+                wtr.write_all(b"\n")?;
+            }
+            for row in rows.drain(..) {
+                wtr.write_all(&row)?;
+                if wconfig.is_line_buffered() {
+                    wtr.flush()?;
+                }
+            }
+        }
+        return Ok(wtr.flush()?);
+    }
+
     let mut rdr = rconfig.reader()?;
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
     let headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
 
-    if !rconfig.no_headers {
+    let mut headers_pending = !rconfig.no_headers;
+    if headers_pending && !args.flag_no_header_if_empty {
         wtr.write_record(&headers)?;
+        headers_pending = false;
     }
+    let mut context = Context::new(args.flag_context);
+    let mut rows = vec![];
     let mut record = csv::ByteRecord::new();
     while rdr.read_byte_record(&mut record)? {
-        let mut m = sel.select(&record).any(|f| pattern.is_match(f));
-        if args.flag_invert_match {
-            m = !m;
+        let matched = is_match(&sel, &record);
+        let gap = context.add(matched, || record.clone(), &mut rows);
+        if headers_pending && !rows.is_empty() {
+            wtr.write_record(&headers)?;
+            headers_pending = false;
         }
-        if m {
-            wtr.write_byte_record(&record)?;
+        if let (true, Some(sep)) = (gap, &args.flag_group_separator) {
+            let mut row = csv::ByteRecord::new();
+            row.push_field(sep.as_bytes());
+            for _ in 1..record.len() {
+                row.push_field(b"");
+            }
+            wtr.write_byte_record(&row)?;
+        }
+        for row in rows.drain(..) {
+            wtr.write_byte_record(&row)?;
         }
     }
     Ok(wtr.flush()?)
 }
+
+/// Decides which rows to write when the rows around each match are also
+/// wanted, keeping the last rows that weren't written in a ring buffer.
+struct Context<T> {
+    size: usize,
+    before: VecDeque<(u64, T)>,
+    after: usize,
+    index: u64,
+    last_written: Option<u64>,
+}
+
+impl<T> Context<T> {
+    fn new(size: usize) -> Context<T> {
+        Context {
+            size: size,
+            before: VecDeque::new(),
+            after: 0,
+            index: 0,
+            last_written: None,
+        }
+    }
+
+    /// Adds the next row, pushing the rows to write now to `out`.
+    ///
+    /// `row` is only called when the row has to be kept. This returns
+    /// whether the rows pushed to `out` don't directly follow the rows
+    /// written previously.
+    fn add<F>(&mut self, matched: bool, row: F, out: &mut Vec<T>) -> bool
+        where F: FnOnce() -> T
+    {
+        let i = self.index;
+        self.index += 1;
+        if !matched && self.after == 0 {
+            if self.size > 0 {
+                self.before.push_back((i, row()));
+                if self.before.len() > self.size {
+                    self.before.pop_front();
+                }
+            }
+            return false;
+        }
+        let first = match self.before.front() {
+            Some(&(j, _)) => j,
+            None => i,
+        };
+        let gap = match self.last_written {
+            Some(last) => first > last + 1,
+            None => false,
+        };
+        out.extend(self.before.drain(..).map(|(_, row)| row));
+        out.push(row());
+        self.after = if matched { self.size } else { self.after - 1 };
+        self.last_written = Some(i);
+        gap
+    }
+}
@@ -1,6 +1,8 @@
 use csv;
+use stats::Commute;
 
 use CliResult;
+use cmd::stats::FieldType;
 use config::{Config, Delimiter};
 use select::SelectColumns;
 use util;
@@ -27,16 +29,67 @@ more indexing). Finally, column ranges can be specified.
   Select the third column named 'Foo':
   $ xsv select 'Foo[2]'
 
+  Mix names and indices in ranges, or leave either end open:
+  $ xsv select Header2-4
+  $ xsv select 2-
+  $ xsv select -Header3
+
+  A name that appears more than once refers to its first occurrence, also at
+  either end of a range, unless it is indexed like 'Foo[2]'.
+
+  If the selection doesn't contain any column, e.g., '!1-', then nothing is
+  written.
+
   Re-order and duplicate columns arbitrarily:
   $ xsv select 3-1,Header3-Header1,Header1,Foo[2],Header1
 
+  Drop two columns by name, keeping all the others in their order:
+  $ xsv select --exclude Header2,Header4
+
+  Move the third and first columns to the front, keeping all the others:
+  $ xsv select --rest 3,1
+
   Quote column names that conflict with selector syntax:
   $ xsv select '\"Date - Opening\",\"Date - Actual Closing\"'
 
+  Select the first and fourth columns with 0-based indices:
+  $ xsv select --zero-based 0,3
+
+  Select only the numeric columns among the first 10 columns:
+  $ xsv select --select-type number 1-10
+
+  Select all columns containing integers, from <stdin> and from a file:
+  $ xsv select --select-type int
+  $ xsv select --select-type int 1- data.csv
+
+  With '--select-type' or '--exclude', the selection may be left out when
+  reading <stdin>, in which case every column is selected. Otherwise, the
+  first argument is always the selection.
+
 Usage:
     xsv select [options] [--] <selection> [<input>]
+    xsv select [options] --select-type <type>
+    xsv select [options] --exclude <cols>
     xsv select --help
 
+select options:
+    --zero-based           Interpret column indices as 0-based instead of
+                           1-based. Names are not affected.
+    --select-type <type>   Only keep the selected columns whose values are
+                           of the given type, which is one of 'int',
+                           'float', 'number' (int or float) or 'string'.
+                           Types are inferred like in 'xsv stats', from
+                           the first records of the data. Columns whose
+                           sampled values are all empty are never kept.
+    --sample <n>           The number of records used to infer column types
+                           for --select-type. [default: 1000]
+    --rest                 Write the selected columns first, followed by all
+                           of the other columns in their original order.
+    --exclude <cols>       Drop these columns from the selection (or from
+                           all columns when no selection is given). This is
+                           like inverting a selection with '!', but can be
+                           combined with one.
+
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
@@ -45,34 +98,106 @@ Common options:
                            sliced, etc.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
     arg_selection: SelectColumns,
+    flag_zero_based: bool,
+    flag_select_type: Option<SelectType>,
+    flag_sample: usize,
+    flag_rest: bool,
+    flag_exclude: Option<SelectColumns>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
 }
 
+#[derive(Clone, Copy, Deserialize)]
+enum SelectType {
+    Int,
+    Float,
+    Number,
+    String,
+}
+
+impl SelectType {
+    fn matches(self, typ: FieldType) -> bool {
+        match self {
+            SelectType::Int => typ == FieldType::TInteger,
+            SelectType::Float => typ == FieldType::TFloat,
+            SelectType::Number => {
+                typ == FieldType::TInteger || typ == FieldType::TFloat
+            }
+            SelectType::String => typ == FieldType::TUnicode,
+        }
+    }
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    let mut select = args.arg_selection;
+    if let Some(ref excluded) = args.flag_exclude {
+        select = select.exclude(excluded.clone());
+    }
 
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers)
-        .select(args.arg_selection);
+        .zero_based(args.flag_zero_based)
+        .select(select);
 
     let mut rdr = rconfig.reader()?;
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
     let headers = rdr.byte_headers()?.clone();
-    let sel = rconfig.selection(&headers)?;
+    let mut sel = rconfig.selection(&headers)?.to_vec();
+
+    // When selecting by type, the sampled records are buffered so that they
+    // can still be written once the columns to keep are known.
+    let mut sample = vec![];
+    if let Some(select_type) = args.flag_select_type {
+        let mut record = csv::ByteRecord::new();
+        while sample.len() < args.flag_sample
+                && rdr.read_byte_record(&mut record)? {
+            sample.push(record.clone());
+        }
+        let mut types = vec![FieldType::default(); headers.len()];
+        for record in &sample {
+            for (typ, field) in types.iter_mut().zip(record) {
+                typ.merge(FieldType::from_sample(field));
+            }
+        }
+        sel.retain(|&i| select_type.matches(types[i]));
+    }
+    if args.flag_rest {
+        // The excluded columns must not come back with the rest.
+        let others = match args.flag_exclude {
+            None => SelectColumns::default(),
+            Some(excluded) => SelectColumns::default().exclude(excluded),
+        };
+        let others = others.selection_with_base(
+            &headers, !rconfig.no_headers, args.flag_zero_based)?;
+        let rest: Vec<usize> =
+            others.iter().cloned().filter(|i| !sel.contains(i)).collect();
+        sel.extend(rest);
+    }
 
-    if !rconfig.no_headers {
+    // Records without any fields can't be written as CSV, so nothing is
+    // written when no column is selected.
+    if sel.is_empty() {
+        return Ok(());
+    }
+    // Empty data has no header row to write.
+    if !rconfig.no_headers && !headers.is_empty() {
         wtr.write_record(sel.iter().map(|&i| &headers[i]))?;
     }
+    for record in &sample {
+        wtr.write_record(sel.iter().map(|&i| &record[i]))?;
+    }
     let mut record = csv::ByteRecord::new();
     while rdr.read_byte_record(&mut record)? {
         wtr.write_record(sel.iter().map(|&i| &record[i]))?;
@@ -1,5 +1,7 @@
 use std::fs;
+use std::io::{self, Write};
 
+use csv;
 
 use CliResult;
 use config::{Config, Delimiter};
@@ -30,6 +32,10 @@ slice options:
     -l, --len <arg>        The length of the slice (can be used instead
                            of --end).
     -i, --index <arg>      Slice a single record (shortcut for -s N -l 1).
+    --raw                  Write rows exactly as they appear in the input
+                           instead of rewriting them. This preserves their
+                           quoting, delimiter and line terminator. An index
+                           is not used in this mode.
 
 Common options:
     -h, --help             Display this message
@@ -39,6 +45,8 @@ Common options:
                            appear in the output as the header row.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
@@ -48,6 +56,7 @@ struct Args {
     flag_end: Option<usize>,
     flag_len: Option<usize>,
     flag_index: Option<usize>,
+    flag_raw: bool,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
@@ -55,6 +64,9 @@ struct Args {
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_raw {
+        return args.raw();
+    }
     match args.rconfig().indexed()? {
         None => args.no_index(),
         Some(idxed) => args.with_index(idxed),
@@ -93,6 +105,28 @@ impl Args {
         Ok(())
     }
 
+    fn raw(&self) -> CliResult<()> {
+        let mut rdr = self.rconfig().raw_reader()?;
+        let wconfig = self.wconfig();
+        let mut wtr = io::BufWriter::new(wconfig.io_writer()?);
+        rdr.byte_headers()?;
+        wtr.write_all(rdr.raw())?;
+
+        let (start, end) = self.range()?;
+        let mut record = csv::ByteRecord::new();
+        let mut i = 0;
+        while i < end && rdr.read_byte_record(&mut record)? {
+            if i >= start {
+                wtr.write_all(rdr.raw())?;
+                if wconfig.is_line_buffered() {
+                    wtr.flush()?;
+                }
+            }
+            i += 1;
+        }
+        Ok(wtr.flush()?)
+    }
+
     fn range(&self) -> Result<(usize, usize), String> {
         util::range(
             self.flag_start, self.flag_end, self.flag_len, self.flag_index)
@@ -1,9 +1,12 @@
 use std::cmp;
 
+use csv;
+use rand::Rng;
+
 use CliResult;
 use config::{Config, Delimiter};
 use select::SelectColumns;
-use util;
+use util::{self, Progress};
 use std::str::from_utf8;
 
 use self::Number::{Float, Int};
@@ -13,6 +16,13 @@ Sorts CSV data lexicographically.
 
 Note that this requires reading all of the CSV data into memory.
 
+Rows that compare equal may be written in any order, unless --stable is
+given, in which case they keep their order from the input.
+
+With --random, rows are shuffled instead. If --select is given as well, then
+rows are still sorted by the selected columns, but rows that compare equal are
+shuffled.
+
 Usage:
     xsv sort [options] [<input>]
 
@@ -21,9 +31,21 @@ sort options:
                            See 'xsv select --help' for the format details.
     -N, --numeric          Compare according to string numerical value
     -R, --reverse          Reverse order
+    -i, --ignore-case      Compare strings case insensitively. This doesn't
+                           change the rows that are written, and has no
+                           effect on numbers compared with --numeric.
+    --stable               Keep rows that compare equal in input order.
+    --random               Shuffle rows, or only rows that compare equal
+                           when --select is given.
+    --seed <number>        RNG seed for --random.
 
 Common options:
     -h, --help             Display this message
+    -P, --progress         Periodically show the number of records read and,
+                           once they are sorted, written on stderr. When an
+                           index exists, the percentage of records read is
+                           shown too. This only has an effect when stderr is
+                           a terminal.
     -o, --output <file>    Write output to <file> instead of stdout.
     -n, --no-headers       When set, the first row will not be interpreted
                            as headers. Namely, it will be sorted with the rest
@@ -31,66 +53,87 @@ Common options:
                            appear as the header row in the output.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
-    flag_select: SelectColumns,
+    flag_select: Option<SelectColumns>,
     flag_numeric: bool,
     flag_reverse: bool,
+    flag_ignore_case: bool,
+    flag_stable: bool,
+    flag_random: bool,
+    flag_seed: Option<usize>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_progress: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let numeric = args.flag_numeric;
     let reverse = args.flag_reverse;
+    let casei = args.flag_ignore_case;
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers)
-        .select(args.flag_select);
+        .select(args.flag_select.clone().unwrap_or_default());
 
     let mut rdr = rconfig.reader()?;
 
     let headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
 
-    let mut all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
-    match (numeric, reverse) {
-        (false, false) =>
-            all.sort_by(|r1, r2| {
-                let a = sel.select(r1);
-                let b = sel.select(r2);
-                iter_cmp(a, b)
-            }),
-        (true, false) =>
-            all.sort_by(|r1, r2| {
-                let a = sel.select(r1);
-                let b = sel.select(r2);
-                iter_cmp_num(a, b)
-            }),
-        (false, true) =>
-            all.sort_by(|r1, r2| {
-                let a = sel.select(r1);
-                let b = sel.select(r2);
-                iter_cmp(b, a)
-            }),
-        (true, true) =>
-            all.sort_by(|r1, r2| {
-                let a = sel.select(r1);
-                let b = sel.select(r2);
-                iter_cmp_num(b, a)
-            }),
+    // The total is only used for display, so a stale or unreadable index
+    // just means that it isn't shown.
+    let total =
+        if args.flag_progress {
+            rconfig.indexed().ok().and_then(|idx| idx.map(|i| i.count()))
+        } else {
+            None
+        };
+    let progress = Progress::labeled(args.flag_progress, total,
+                                     "records read");
+    let mut all = rdr.byte_records()
+        .inspect(|_| progress.inc())
+        .collect::<Result<Vec<_>, _>>()?;
+    progress.finish();
+    let compare = |r1: &csv::ByteRecord, r2: &csv::ByteRecord| {
+        let a = sel.select(r1);
+        let b = sel.select(r2);
+        let ord = match (numeric, casei) {
+            (true, _) => iter_cmp_num(a, b),
+            (false, true) => iter_cmp_casei(a, b),
+            (false, false) => iter_cmp(a, b),
+        };
+        if reverse { ord.reverse() } else { ord }
+    };
+    if args.flag_random {
+        // A stable sort of shuffled rows shuffles rows that compare equal.
+        util::acquire_rng(args.flag_seed).shuffle(&mut all);
+        if args.flag_select.is_some() {
+            all.sort_by(compare);
+        }
+    } else if args.flag_stable {
+        all.sort_by(compare);
+    } else {
+        all.sort_unstable_by(compare);
     }
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
     rconfig.write_headers(&mut rdr, &mut wtr)?;
+    let progress = Progress::labeled(args.flag_progress,
+                                     Some(all.len() as u64),
+                                     "records written");
     for r in all.into_iter() {
         wtr.write_byte_record(&r)?;
+        progress.inc();
     }
+    progress.finish();
     Ok(wtr.flush()?)
 }
 
@@ -110,6 +153,37 @@ pub fn iter_cmp<A, L, R>(mut a: L, mut b: R) -> cmp::Ordering
     }
 }
 
+/// Order `a` and `b` lexicographically, ignoring case
+pub fn iter_cmp_casei<'a, L, R>(mut a: L, mut b: R) -> cmp::Ordering
+        where L: Iterator<Item=&'a [u8]>, R: Iterator<Item=&'a [u8]> {
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return cmp::Ordering::Equal,
+            (None, _   ) => return cmp::Ordering::Less,
+            (_   , None) => return cmp::Ordering::Greater,
+            (Some(x), Some(y)) => match compare_casei(x, y) {
+                cmp::Ordering::Equal => (),
+                non_eq => return non_eq,
+            },
+        }
+    }
+}
+
+fn compare_casei(x: &[u8], y: &[u8]) -> cmp::Ordering {
+    match (from_utf8(x), from_utf8(y)) {
+        (Ok(x), Ok(y)) => {
+            let x = x.chars().flat_map(char::to_lowercase);
+            let y = y.chars().flat_map(char::to_lowercase);
+            x.cmp(y)
+        }
+        _ => {
+            let x = x.iter().map(u8::to_ascii_lowercase);
+            let y = y.iter().map(u8::to_ascii_lowercase);
+            x.cmp(y)
+        }
+    }
+}
+
 /// Try parsing `a` and `b` as numbers when ordering
 pub fn iter_cmp_num<'a, L, R>(mut a: L, mut b: R) -> cmp::Ordering
         where L: Iterator<Item=&'a [u8]>, R: Iterator<Item=&'a [u8]> {
@@ -0,0 +1,130 @@
+use std::cmp;
+use std::io::{self, Write};
+use std::str;
+
+use tabwriter::TabWriter;
+
+use CliResult;
+use config::{Config, Delimiter};
+use select::SelectColumns;
+use util;
+
+static USAGE: &'static str = "
+Prints a sparkline summarizing the distribution of each numeric column.
+
+The range of values of each column is split into buckets of equal width, and
+each bucket is drawn with a character whose height is proportional to the
+number of values that fall into it:
+
+    field  sparkline             min  max
+    age    ▁▂▄▆██▇▆▅▄▃▂▂▁▁▁▁▁▁▁  0    97
+
+Empty buckets are drawn with the lowest character, while any non-empty bucket
+is drawn at least one step higher. Values that aren't numbers are ignored, and
+columns without any number have an empty sparkline.
+
+This requires storing all numbers of the selected columns in memory.
+
+Usage:
+    xsv sparkline [options] [<input>]
+
+sparkline options:
+    -s, --select <arg>     Select a subset of columns to draw sparklines for.
+                           See 'xsv select --help' for the format details.
+    -b, --bins <n>         The number of buckets, i.e., the width of each
+                           sparkline. [default: 20]
+
+Common options:
+    -h, --help             Display this message
+    -n, --no-headers       When set, the first row will NOT be interpreted
+                           as column names. Columns are then named by their
+                           1-based index.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_bins: usize,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_bins == 0 {
+        return fail!("--bins must be greater than 0.");
+    }
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+
+    let mut columns: Vec<Vec<f64>> = vec![vec![]; sel.len()];
+    for row in rdr.byte_records() {
+        let row = row?;
+        for (nums, field) in columns.iter_mut().zip(sel.select(&row)) {
+            let n = str::from_utf8(field).ok()
+                .and_then(|s| s.trim().parse::<f64>().ok());
+            match n {
+                Some(n) if n.is_finite() => nums.push(n),
+                _ => {}
+            }
+        }
+    }
+
+    let mut wtr = TabWriter::new(io::stdout());
+    writeln!(&mut wtr, "field\tsparkline\tmin\tmax")?;
+    for (&i, nums) in sel.iter().zip(columns) {
+        if args.flag_no_headers {
+            write!(&mut wtr, "{}", i + 1)?;
+        } else {
+            wtr.write_all(&headers[i])?;
+        }
+        match sparkline(&nums, args.flag_bins) {
+            None => writeln!(&mut wtr)?,
+            Some((line, min, max)) => {
+                writeln!(&mut wtr, "\t{}\t{}\t{}", line, min, max)?
+            }
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Returns the sparkline of `nums` using `bins` buckets, along with the
+/// minimum and maximum of `nums`. If `nums` is empty, `None` is returned.
+fn sparkline(nums: &[f64], bins: usize) -> Option<(String, f64, f64)> {
+    if nums.is_empty() {
+        return None;
+    }
+    let min = nums.iter().cloned().fold(nums[0], f64::min);
+    let max = nums.iter().cloned().fold(nums[0], f64::max);
+    let mut counts = vec![0u64; bins];
+    for &n in nums {
+        let i =
+            if max == min {
+                0
+            } else {
+                ((n - min) / (max - min) * bins as f64) as usize
+            };
+        counts[cmp::min(i, bins - 1)] += 1;
+    }
+    let most = *counts.iter().max().unwrap() as f64;
+    let last = (BARS.len() - 1) as f64;
+    let line = counts.iter().map(|&c| {
+        // Round up so that non-empty buckets are never drawn as empty ones.
+        BARS[(c as f64 * last / most).ceil() as usize]
+    }).collect();
+    Some((line, min, max))
+}
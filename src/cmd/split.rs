@@ -7,7 +7,7 @@ use csv;
 use threadpool::ThreadPool;
 
 use CliResult;
-use config::{Config, Delimiter};
+use config::{self, Config, Delimiter};
 use index::Indexed;
 use util::{self, FilenameTemplate};
 
@@ -44,6 +44,8 @@ Common options:
                            appear in all chunks as the header row.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Clone, Deserialize)]
@@ -128,7 +130,7 @@ impl Args {
         &self,
         headers: &csv::ByteRecord,
         start: usize,
-    ) -> CliResult<csv::Writer<Box<io::Write+'static>>> {
+    ) -> CliResult<config::Writer<Box<io::Write+'static>>> {
         let dir = Path::new(&self.arg_outdir);
         let path = dir.join(self.flag_filename.filename(&format!("{}", start)));
         let spath = Some(path.display().to_string());
@@ -1,7 +1,8 @@
 use std::borrow::ToOwned;
+use std::cmp::{self, Reverse};
+use std::collections::{BTreeSet, HashMap};
 use std::default::Default;
 use std::fmt;
-use std::fs;
 use std::io;
 use std::iter::{FromIterator, repeat};
 use std::str::{self, FromStr};
@@ -13,9 +14,9 @@ use threadpool::ThreadPool;
 
 use CliResult;
 use config::{Config, Delimiter};
-use index::Indexed;
+use index::{Indexed, MemoryIndex};
 use select::{SelectColumns, Selection};
-use util;
+use util::{self, Progress};
 
 use self::FieldType::{TUnknown, TNull, TUnicode, TFloat, TInteger};
 
@@ -40,15 +41,51 @@ stats options:
                            See 'xsv select --help' for the format details.
                            This is provided here because piping 'xsv select'
                            into 'xsv stats' will disable the use of indexing.
-    --everything           Show all statistics available.
+    --exclude <arg>        Don't compute stats for these columns, even when
+                           they are selected by --select.
+    --everything           Show all statistics available. This includes
+                           the shortest and longest values of each column
+                           (ties are broken by picking the smallest and
+                           largest value, respectively).
     --mode                 Show the mode.
                            This requires storing all CSV data in memory.
     --cardinality          Show the cardinality.
                            This requires storing all CSV data in memory.
     --median               Show the median.
                            This requires storing all CSV data in memory.
+    --approx-cardinality   Show an estimate of the cardinality computed with
+                           a HyperLogLog sketch. Unlike --cardinality, this
+                           uses a small constant amount of memory (16KB per
+                           column) and the standard error of the estimate is
+                           about 0.8%. This is not enabled by --everything.
+    --approx-quantiles     Show estimates of the first quartile, the median
+                           and the third quartile computed with a t-digest.
+                           Unlike --median, this uses a small constant
+                           amount of memory and works in parallel. The
+                           estimates are usually within 1% of the true rank,
+                           and are more accurate for quantiles close to 0 or
+                           1 than for the median. This is not enabled by
+                           --everything.
+    --top <k>              Show the <k> most frequent values of each column
+                           along with their counts. This uses a Space-Saving
+                           sketch tracking 10 * <k> candidates in constant
+                           memory, so counts may be overestimated when many
+                           distinct values are about as frequent. Empty
+                           values are ignored.
+    --decimal              Sum floating point columns exactly, as decimal
+                           numbers, so that e.g. 0.1 + 0.2 is 0.3. The sum
+                           is written with as many decimal places as the
+                           most precise value, without any rounding.
+                           Columns with values in scientific notation
+                           (e.g., 1e-3) are summed as usual.
+    --round <n>            Round floating point statistics (e.g., mean and
+                           standard deviation) to <n> decimal places.
+                           Integer statistics are never changed.
     --nulls                Include NULLs in the population size for computing
                            mean and standard deviation.
+    --null-value <list>    A comma separated list of values, e.g., 'NA,NULL',
+                           that are treated like empty values. They are then
+                           NULL and don't change the type of a column.
     -j, --jobs <arg>       The number of jobs to run in parallel.
                            This works better when the given CSV data has
                            an index already created. Note that a file handle
@@ -56,30 +93,50 @@ stats options:
                            When set to '0', the number of jobs is set to the
                            number of CPUs detected.
                            [default: 0]
+    --memory-index         When no index exists for the CSV data, build one
+                           in memory so that statistics can still be
+                           computed in parallel. This requires an extra pass
+                           over the data and is ignored for <stdin>.
+    --head <n>             Only read the first <n> records of the input.
 
 Common options:
     -h, --help             Display this message
+    -P, --progress         Periodically show the number of records processed
+                           on stderr. This only has an effect when stderr is
+                           a terminal.
     -o, --output <file>    Write output to <file> instead of stdout.
     -n, --no-headers       When set, the first row will NOT be interpreted
                            as column names. i.e., They will be included
                            in statistics.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Clone, Deserialize)]
 struct Args {
     arg_input: Option<String>,
     flag_select: SelectColumns,
+    flag_exclude: Option<SelectColumns>,
     flag_everything: bool,
     flag_mode: bool,
     flag_cardinality: bool,
     flag_median: bool,
+    flag_approx_cardinality: bool,
+    flag_approx_quantiles: bool,
+    flag_top: Option<usize>,
+    flag_decimal: bool,
+    flag_round: Option<u32>,
     flag_nulls: bool,
+    flag_null_value: Option<String>,
     flag_jobs: usize,
+    flag_memory_index: bool,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_progress: bool,
+    flag_head: Option<u64>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -87,14 +144,21 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let (headers, stats) = match args.rconfig().indexed()? {
-        None => args.sequential_stats(),
-        Some(idx) => {
-            if args.flag_jobs == 1 {
-                args.sequential_stats()
-            } else {
-                args.parallel_stats(idx)
+        Some(ref idx) if args.flag_jobs != 1 => {
+            let count = args.rconfig().limit_count(idx.count());
+            args.parallel_stats(count, None)
+        }
+        None if args.flag_memory_index && args.flag_jobs != 1 => {
+            match args.rconfig().memory_index()? {
+                None => args.sequential_stats(),
+                Some(mem) => {
+                    let idx = args.rconfig().indexed_in_memory(mem.clone())?;
+                    let count = args.rconfig().limit_count(idx.count());
+                    args.parallel_stats(count, Some(mem))
+                }
             }
         }
+        _ => args.sequential_stats(),
     }?;
     let stats = args.stats_to_records(stats);
 
@@ -116,41 +180,70 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
 impl Args {
     fn sequential_stats(&self) -> CliResult<(csv::ByteRecord, Vec<Stats>)> {
-        let mut rdr = self.rconfig().reader()?;
+        let rconfig = self.rconfig();
+        let mut rdr = rconfig.reader()?;
         let (headers, sel) = self.sel_headers(&mut rdr)?;
-        let stats = self.compute(&sel, rdr.byte_records())?;
+        let progress = Progress::new(self.flag_progress, None);
+        let it = rconfig.limit_records(rdr.byte_records());
+        let stats = self.compute(&sel, it, &progress)?;
+        progress.finish();
         Ok((headers, stats))
     }
 
+    /// Computes statistics in parallel over `count` records.
+    ///
+    /// Each job opens the index file for the CSV data, unless `mem` is set,
+    /// in which case the given in-memory index is used instead.
     fn parallel_stats(
         &self,
-        idx: Indexed<fs::File, fs::File>,
+        count: u64,
+        mem: Option<MemoryIndex>,
     ) -> CliResult<(csv::ByteRecord, Vec<Stats>)> {
         // N.B. This method doesn't handle the case when the number of records
         // is zero correctly. So we use `sequential_stats` instead.
-        if idx.count() == 0 {
+        if count == 0 {
             return self.sequential_stats();
         }
 
         let mut rdr = self.rconfig().reader()?;
         let (headers, sel) = self.sel_headers(&mut rdr)?;
 
-        let chunk_size = util::chunk_size(idx.count() as usize, self.njobs());
-        let nchunks = util::num_of_chunks(idx.count() as usize, chunk_size);
+        let chunk_size = util::chunk_size(count as usize, self.njobs());
+        let nchunks = util::num_of_chunks(count as usize, chunk_size);
 
+        let progress = Progress::new(self.flag_progress, Some(count));
         let pool = ThreadPool::new(self.njobs());
         let (send, recv) = channel::bounded(0);
         for i in 0..nchunks {
-            let (send, args, sel) = (send.clone(), self.clone(), sel.clone());
+            let (send, args, sel, mem, progress) = (
+                send.clone(), self.clone(), sel.clone(), mem.clone(),
+                progress.clone(),
+            );
             pool.execute(move || {
-                let mut idx = args.rconfig().indexed().unwrap().unwrap();
-                idx.seek((i * chunk_size) as u64).unwrap();
-                let it = idx.byte_records().take(chunk_size);
-                send.send(args.compute(&sel, it).unwrap());
+                let start = (i * chunk_size) as u64;
+                // The last chunk must not read past `count`, which may be
+                // less than the number of indexed records.
+                let len = cmp::min(chunk_size as u64, count - start) as usize;
+                let stats = match mem {
+                    None => {
+                        let idx = args.rconfig().indexed().unwrap().unwrap();
+                        args.compute_chunk(
+                            &sel, idx, start, len, &progress)
+                    }
+                    Some(mem) => {
+                        let idx =
+                            args.rconfig().indexed_in_memory(mem).unwrap();
+                        args.compute_chunk(
+                            &sel, idx, start, len, &progress)
+                    }
+                };
+                send.send(stats.unwrap());
             });
         }
         drop(send);
-        Ok((headers, merge_all(recv).unwrap_or_else(Vec::new)))
+        let stats = merge_all(recv).unwrap_or_else(Vec::new);
+        progress.finish();
+        Ok((headers, stats))
     }
 
     fn stats_to_records(&self, stats: Vec<Stats>) -> Vec<csv::StringRecord> {
@@ -170,11 +263,18 @@ impl Args {
         records
     }
 
-    fn compute<I>(&self, sel: &Selection, it: I) -> CliResult<Vec<Stats>>
-            where I: Iterator<Item=csv::Result<csv::ByteRecord>> {
+    fn compute<I>(
+        &self,
+        sel: &Selection,
+        it: I,
+        progress: &Progress,
+    ) -> CliResult<Vec<Stats>>
+    where I: Iterator<Item=csv::Result<csv::ByteRecord>>
+    {
         let mut stats = self.new_stats(sel.len());
         for row in it {
             let row = row?;
+            progress.inc();
             for (i, field) in sel.select(&row).enumerate() {
                 stats[i].add(field);
             }
@@ -182,6 +282,20 @@ impl Args {
         Ok(stats)
     }
 
+    fn compute_chunk<R, I>(
+        &self,
+        sel: &Selection,
+        mut idx: Indexed<R, I>,
+        start: u64,
+        len: usize,
+        progress: &Progress,
+    ) -> CliResult<Vec<Stats>>
+    where R: io::Read + io::Seek, I: io::Read + io::Seek
+    {
+        idx.seek(start)?;
+        self.compute(sel, idx.byte_records().take(len), progress)
+    }
+
     fn sel_headers<R: io::Read>(
         &self,
         rdr: &mut csv::Reader<R>,
@@ -192,10 +306,15 @@ impl Args {
     }
 
     fn rconfig(&self) -> Config {
+        let mut select = self.flag_select.clone();
+        if let Some(ref excluded) = self.flag_exclude {
+            select = select.exclude(excluded.clone());
+        }
         Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
             .no_headers(self.flag_no_headers)
-            .select(self.flag_select.clone())
+            .select(select)
+            .head(self.flag_head)
     }
 
     fn njobs(&self) -> usize {
@@ -205,10 +324,22 @@ impl Args {
     fn new_stats(&self, record_len: usize) -> Vec<Stats> {
         repeat(Stats::new(WhichStats {
             include_nulls: self.flag_nulls,
+            null_values: match self.flag_null_value {
+                None => vec![],
+                Some(ref list) => {
+                    list.split(',').map(|v| v.as_bytes().to_vec()).collect()
+                }
+            },
             sum: true,
+            decimal: self.flag_decimal,
             range: true,
             dist: true,
             cardinality: self.flag_cardinality || self.flag_everything,
+            approx_cardinality: self.flag_approx_cardinality,
+            approx_quantiles: self.flag_approx_quantiles,
+            length_values: self.flag_everything,
+            top: self.flag_top.unwrap_or(0),
+            round: self.flag_round,
             median: self.flag_median || self.flag_everything,
             mode: self.flag_mode || self.flag_everything,
         })).take(record_len).collect()
@@ -223,6 +354,19 @@ impl Args {
         if self.flag_median || all { fields.push("median"); }
         if self.flag_mode || all { fields.push("mode"); }
         if self.flag_cardinality || all { fields.push("cardinality"); }
+        if all {
+            fields.push("min_length_value");
+            fields.push("max_length_value");
+        }
+        if self.flag_approx_cardinality {
+            fields.push("approx_cardinality");
+        }
+        if self.flag_approx_quantiles {
+            fields.push("approx_q1");
+            fields.push("approx_median");
+            fields.push("approx_q3");
+        }
+        if self.flag_top.unwrap_or(0) > 0 { fields.push("top"); }
         csv::StringRecord::from(fields)
     }
 }
@@ -230,10 +374,17 @@ impl Args {
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct WhichStats {
     include_nulls: bool,
+    null_values: Vec<Vec<u8>>,
     sum: bool,
+    decimal: bool,
     range: bool,
     dist: bool,
     cardinality: bool,
+    approx_cardinality: bool,
+    approx_quantiles: bool,
+    length_values: bool,
+    top: usize,
+    round: Option<u32>,
     median: bool,
     mode: bool,
 }
@@ -252,6 +403,10 @@ struct Stats {
     online: Option<OnlineStats>,
     mode: Option<Unsorted<Vec<u8>>>,
     median: Option<Unsorted<f64>>,
+    hll: Option<HyperLogLog>,
+    digest: Option<TDigest>,
+    top: Option<SpaceSaving>,
+    length_values: Option<MinMax<(usize, Vec<u8>)>>,
     which: WhichStats,
 }
 
@@ -259,11 +414,35 @@ impl Stats {
     fn new(which: WhichStats) -> Stats {
         let (mut sum, mut minmax, mut online, mut mode, mut median) =
             (None, None, None, None, None);
-        if which.sum { sum = Some(Default::default()); }
+        if which.sum { sum = Some(TypedSum::new(which.decimal)); }
         if which.range { minmax = Some(Default::default()); }
         if which.dist { online = Some(Default::default()); }
         if which.mode || which.cardinality { mode = Some(Default::default()); }
         if which.median { median = Some(Default::default()); }
+        let hll =
+            if which.approx_cardinality {
+                Some(HyperLogLog::new())
+            } else {
+                None
+            };
+        let digest =
+            if which.approx_quantiles {
+                Some(TDigest::new())
+            } else {
+                None
+            };
+        let length_values =
+            if which.length_values {
+                Some(Default::default())
+            } else {
+                None
+            };
+        let top =
+            if which.top > 0 {
+                Some(SpaceSaving::new(10 * which.top))
+            } else {
+                None
+            };
         Stats {
             typ: Default::default(),
             sum: sum,
@@ -271,11 +450,21 @@ impl Stats {
             online: online,
             mode: mode,
             median: median,
+            hll: hll,
+            digest: digest,
+            top: top,
+            length_values: length_values,
             which: which,
         }
     }
 
     fn add(&mut self, sample: &[u8]) {
+        let sample =
+            if self.which.null_values.iter().any(|v| &**v == sample) {
+                &b""[..]
+            } else {
+                sample
+            };
         let sample_type = FieldType::from_sample(sample);
         self.typ.merge(sample_type);
 
@@ -283,6 +472,17 @@ impl Stats {
         self.sum.as_mut().map(|v| v.add(t, sample));
         self.minmax.as_mut().map(|v| v.add(t, sample));
         self.mode.as_mut().map(|v| v.add(sample.to_vec()));
+        if let Some(ref mut hll) = self.hll {
+            hll.add(sample);
+        }
+        if let Some(ref mut lv) = self.length_values {
+            lv.add((sample.len(), sample.to_vec()));
+        }
+        if let Some(ref mut top) = self.top {
+            if !sample.is_empty() {
+                top.add(sample);
+            }
+        }
         match self.typ {
             TUnknown => {}
             TNull => {
@@ -299,6 +499,7 @@ impl Stats {
                 } else {
                     let n = from_bytes::<f64>(sample).unwrap();
                     self.median.as_mut().map(|v| { v.add(n); });
+                    self.digest.as_mut().map(|v| { v.add(n); });
                     self.online.as_mut().map(|v| { v.add(n); });
                 }
             }
@@ -307,15 +508,16 @@ impl Stats {
 
     fn to_record(&mut self) -> csv::StringRecord {
         let typ = self.typ;
+        let round = self.which.round;
         let mut pieces = vec![];
         let empty = || "".to_owned();
 
         pieces.push(self.typ.to_string());
-        match self.sum.as_ref().and_then(|sum| sum.show(typ)) {
+        match self.sum.as_ref().and_then(|sum| sum.show(typ, round)) {
             Some(sum) => { pieces.push(sum); }
             None => { pieces.push(empty()); }
         }
-        match self.minmax.as_ref().and_then(|mm| mm.show(typ)) {
+        match self.minmax.as_ref().and_then(|mm| mm.show(typ, round)) {
             Some(mm) => { pieces.push(mm.0); pieces.push(mm.1); }
             None => { pieces.push(empty()); pieces.push(empty()); }
         }
@@ -329,8 +531,8 @@ impl Stats {
         } else {
             match self.online {
                 Some(ref v) => {
-                    pieces.push(v.mean().to_string());
-                    pieces.push(v.stddev().to_string());
+                    pieces.push(format_float(v.mean(), round));
+                    pieces.push(format_float(v.stddev(), round));
                 }
                 None => { pieces.push(empty()); pieces.push(empty()); }
             }
@@ -341,7 +543,7 @@ impl Stats {
                     pieces.push(empty());
                 }
             }
-            Some(v) => { pieces.push(v.to_string()); }
+            Some(v) => { pieces.push(format_float(v, round)); }
         }
         match self.mode.as_mut() {
             None => {
@@ -365,6 +567,37 @@ impl Stats {
                 }
             }
         }
+        if let Some(ref lv) = self.length_values {
+            let lossy = |v: Option<&(usize, Vec<u8>)>| -> String {
+                v.map_or(empty(), |v| {
+                    String::from_utf8_lossy(&v.1).into_owned()
+                })
+            };
+            pieces.push(lossy(lv.min()));
+            pieces.push(lossy(lv.max()));
+        }
+        if let Some(ref hll) = self.hll {
+            pieces.push(hll.estimate().to_string());
+        }
+        if let Some(ref mut digest) = self.digest {
+            for &q in &[0.25, 0.5, 0.75] {
+                match digest.quantile(q) {
+                    Some(v) if typ.is_number() => {
+                        pieces.push(format_float(v, round));
+                    }
+                    _ => pieces.push(empty()),
+                }
+            }
+        }
+        if let Some(ref top) = self.top {
+            let values: Vec<String> = top.top(self.which.top)
+                .into_iter()
+                .map(|(v, c)| {
+                    format!("{} ({})", String::from_utf8_lossy(v), c)
+                })
+                .collect();
+            pieces.push(values.join(", "));
+        }
         csv::StringRecord::from(pieces)
     }
 }
@@ -377,12 +610,16 @@ impl Commute for Stats {
         self.online.merge(other.online);
         self.mode.merge(other.mode);
         self.median.merge(other.median);
+        self.hll.merge(other.hll);
+        self.digest.merge(other.digest);
+        self.top.merge(other.top);
+        self.length_values.merge(other.length_values);
         self.which.merge(other.which);
     }
 }
 
 #[derive(Clone, Copy, PartialEq)]
-enum FieldType {
+pub enum FieldType {
     TUnknown,
     TNull,
     TUnicode,
@@ -391,7 +628,7 @@ enum FieldType {
 }
 
 impl FieldType {
-    fn from_sample(sample: &[u8]) -> FieldType {
+    pub fn from_sample(sample: &[u8]) -> FieldType {
         if sample.is_empty() {
             return TNull;
         }
@@ -451,20 +688,330 @@ impl fmt::Display for FieldType {
     }
 }
 
+/// HyperLogLog estimates the number of distinct values seen in constant
+/// memory.
+///
+/// This uses 2^14 registers, which gives a standard error of about
+/// 1.04 / sqrt(2^14), i.e., 0.8%.
+#[derive(Clone)]
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+const HLL_PRECISION: u32 = 14;
+
+impl HyperLogLog {
+    fn new() -> HyperLogLog {
+        HyperLogLog { registers: vec![0; 1 << HLL_PRECISION] }
+    }
+
+    fn add(&mut self, sample: &[u8]) {
+        let hash = mix64(util::fnv_hash(sample));
+        let idx = (hash >> (64 - HLL_PRECISION)) as usize;
+        // The remaining bits, with a sentinel bit so that the rank is
+        // bounded even when they are all zero.
+        let rest = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if rank > self.registers[idx] {
+            self.registers[idx] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter()
+            .map(|&r| 1.0 / (1u64 << r) as f64)
+            .sum();
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // Use linear counting for small cardinalities.
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+impl Commute for HyperLogLog {
+    fn merge(&mut self, other: HyperLogLog) {
+        for (r1, r2) in self.registers.iter_mut().zip(other.registers) {
+            if r2 > *r1 {
+                *r1 = r2;
+            }
+        }
+    }
+}
+
+/// TDigest estimates quantiles in constant memory.
+///
+/// Values are grouped into clusters (centroids) that are kept sorted by their
+/// mean. Clusters near the median may hold many values, while clusters near
+/// the extremes stay small, which is what makes extreme quantiles more
+/// accurate. New values are buffered and merged into the clusters in
+/// batches.
+#[derive(Clone)]
+struct TDigest {
+    /// Pairs of (mean, weight), sorted by mean.
+    centroids: Vec<(f64, f64)>,
+    /// Pairs of (mean, weight) that haven't been merged into `centroids`.
+    buffer: Vec<(f64, f64)>,
+    min: f64,
+    max: f64,
+}
+
+/// Bounds the number of centroids, which is roughly twice this value.
+const TDIGEST_COMPRESSION: f64 = 100.0;
+const TDIGEST_BUFFER: usize = 1000;
+
+impl TDigest {
+    fn new() -> TDigest {
+        TDigest {
+            centroids: vec![],
+            buffer: Vec::with_capacity(TDIGEST_BUFFER),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn add(&mut self, n: f64) {
+        if n.is_nan() {
+            return;
+        }
+        self.min = self.min.min(n);
+        self.max = self.max.max(n);
+        self.buffer.push((n, 1.0));
+        if self.buffer.len() >= TDIGEST_BUFFER {
+            self.compress();
+        }
+    }
+
+    /// Merges the buffered values into the centroids.
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut points = self.buffer.split_off(0);
+        points.append(&mut self.centroids);
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let total: f64 = points.iter().map(|p| p.1).sum();
+
+        let mut so_far = 0.0;
+        let mut cur = points[0];
+        for &(mean, weight) in &points[1..] {
+            let proposed = cur.1 + weight;
+            let q = (so_far + proposed / 2.0) / total;
+            let limit = 4.0 * total * q * (1.0 - q) / TDIGEST_COMPRESSION;
+            if proposed <= limit {
+                cur.0 += (mean - cur.0) * weight / proposed;
+                cur.1 = proposed;
+            } else {
+                so_far += cur.1;
+                self.centroids.push(cur);
+                cur = (mean, weight);
+            }
+        }
+        self.centroids.push(cur);
+    }
+
+    /// Returns an estimate of the `q` quantile, with `0 <= q <= 1`.
+    ///
+    /// Each centroid is assumed to sit at the middle of the ranks it covers,
+    /// and quantiles between centroids are interpolated linearly.
+    fn quantile(&mut self, q: f64) -> Option<f64> {
+        self.compress();
+        if self.centroids.is_empty() {
+            return None;
+        }
+        let total: f64 = self.centroids.iter().map(|c| c.1).sum();
+        let rank = q * total;
+        let (mut prev_mean, mut prev_rank) = (self.min, 0.0);
+        let mut so_far = 0.0;
+        for &(mean, weight) in &self.centroids {
+            let center = so_far + weight / 2.0;
+            if rank <= center {
+                if center <= prev_rank {
+                    return Some(mean);
+                }
+                let t = (rank - prev_rank) / (center - prev_rank);
+                return Some(prev_mean + t * (mean - prev_mean));
+            }
+            prev_mean = mean;
+            prev_rank = center;
+            so_far += weight;
+        }
+        if total <= prev_rank {
+            return Some(self.max);
+        }
+        let t = (rank - prev_rank) / (total - prev_rank);
+        Some(prev_mean + t * (self.max - prev_mean))
+    }
+}
+
+impl Commute for TDigest {
+    fn merge(&mut self, other: TDigest) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.buffer.extend(other.centroids);
+        self.buffer.extend(other.buffer);
+        self.compress();
+    }
+}
+
+/// SpaceSaving approximates the most frequent values seen in constant memory.
+///
+/// At most `capacity` values are tracked. When a new value is seen and every
+/// slot is taken, the least frequent value is replaced and the new value
+/// inherits its count (plus one). Counts are therefore upper bounds, but the
+/// most frequent values are reliably found when the data is skewed.
+///
+/// The counters are also kept ordered by count, so that finding the least
+/// frequent value doesn't require scanning all of them.
+#[derive(Clone)]
+struct SpaceSaving {
+    capacity: usize,
+    counts: HashMap<Vec<u8>, u64>,
+    /// The same counters as `counts`, ordered by ascending count. Ties are
+    /// ordered by descending value, so that the greatest value is evicted
+    /// first.
+    by_count: BTreeSet<(u64, Reverse<Vec<u8>>)>,
+}
+
+impl SpaceSaving {
+    fn new(capacity: usize) -> SpaceSaving {
+        SpaceSaving {
+            capacity: capacity,
+            counts: HashMap::new(),
+            by_count: BTreeSet::new(),
+        }
+    }
+
+    fn add(&mut self, sample: &[u8]) {
+        if let Some(count) = self.counts.get_mut(sample) {
+            let mut key = (*count, Reverse(sample.to_vec()));
+            self.by_count.remove(&key);
+            *count += 1;
+            key.0 = *count;
+            self.by_count.insert(key);
+            return;
+        }
+        let mut count = 1;
+        if self.counts.len() >= self.capacity {
+            let min = self.by_count.iter().next().cloned().unwrap();
+            self.by_count.remove(&min);
+            self.counts.remove(&(min.1).0);
+            count += min.0;
+        }
+        self.counts.insert(sample.to_vec(), count);
+        self.by_count.insert((count, Reverse(sample.to_vec())));
+    }
+
+    /// Returns the `k` most frequent values, ordered by descending count.
+    fn top(&self, k: usize) -> Vec<(&[u8], u64)> {
+        let mut top: Vec<(&[u8], u64)> =
+            self.counts.iter().map(|(v, &c)| (&**v, c)).collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        top.truncate(k);
+        top
+    }
+
+    fn min_count(&self) -> u64 {
+        if self.counts.len() < self.capacity {
+            0
+        } else {
+            self.by_count.iter().next().map_or(0, |c| c.0)
+        }
+    }
+}
+
+impl Commute for SpaceSaving {
+    fn merge(&mut self, other: SpaceSaving) {
+        // A value missing from a full sketch may have been seen up to that
+        // sketch's minimum count times, so that is added as its count.
+        let (min1, min2) = (self.min_count(), other.min_count());
+        for count in self.counts.values_mut() {
+            *count += min2;
+        }
+        for (v, c) in other.counts {
+            let count = self.counts.entry(v).or_insert(min1 + min2);
+            *count += c - min2;
+        }
+        let mut all: Vec<(Vec<u8>, u64)> = self.counts.drain().collect();
+        all.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        all.truncate(self.capacity);
+        self.by_count = all.iter()
+            .map(|&(ref v, c)| (c, Reverse(v.clone())))
+            .collect();
+        self.counts = all.into_iter().collect();
+    }
+}
+
+/// Scrambles the bits of `h`, since HyperLogLog needs every bit of its hash
+/// to be evenly distributed. (This is the finalizer of MurmurHash3.)
+fn mix64(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51afd7ed558ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+    h ^= h >> 33;
+    h
+}
+
+/// Formats `f`, rounded to `round` decimal places if given.
+///
+/// Trailing zeros are dropped after rounding, and a value that rounds to
+/// zero is written as `0`, never `-0`.
+fn format_float(f: f64, round: Option<u32>) -> String {
+    let places = match round {
+        None => return f.to_string(),
+        Some(places) => places as usize,
+    };
+    let mut s = format!("{:.*}", places, f);
+    if s.contains('.') {
+        while s.ends_with('0') {
+            s.pop();
+        }
+        if s.ends_with('.') {
+            s.pop();
+        }
+    }
+    if s == "-0" {
+        s = "0".to_owned();
+    }
+    s
+}
+
 /// TypedSum keeps a rolling sum of the data seen.
 ///
 /// It sums integers until it sees a float, at which point it sums floats.
+/// When `decimal` is set, an exact decimal sum is also kept, until a value
+/// can't be read as a decimal number.
 #[derive(Clone, Default)]
 struct TypedSum {
     integer: i64,
     float: Option<f64>,
+    decimal: Option<Decimal>,
 }
 
 impl TypedSum {
+    fn new(decimal: bool) -> TypedSum {
+        TypedSum {
+            decimal: if decimal { Some(Decimal::default()) } else { None },
+            ..TypedSum::default()
+        }
+    }
+
     fn add(&mut self, typ: FieldType, sample: &[u8]) {
         if sample.is_empty() {
             return;
         }
+        if let Some(sum) = self.decimal {
+            if typ.is_number() {
+                self.decimal =
+                    Decimal::parse(sample).and_then(|n| sum.checked_add(n));
+            }
+        }
         match typ {
             TFloat => {
                 let float: f64 = from_bytes::<f64>(sample).unwrap();
@@ -488,17 +1035,26 @@ impl TypedSum {
         }
     }
 
-    fn show(&self, typ: FieldType) -> Option<String> {
+    fn show(&self, typ: FieldType, round: Option<u32>) -> Option<String> {
         match typ {
             TNull | TUnicode | TUnknown  => None,
             TInteger => Some(self.integer.to_string()),
-            TFloat => Some(self.float.unwrap_or(0.0).to_string()),
+            TFloat => match self.decimal {
+                Some(sum) => Some(sum.to_string()),
+                None => {
+                    Some(format_float(self.float.unwrap_or(0.0), round))
+                }
+            },
         }
     }
 }
 
 impl Commute for TypedSum {
     fn merge(&mut self, other: TypedSum) {
+        self.decimal = match (self.decimal, other.decimal) {
+            (Some(d1), Some(d2)) => d1.checked_add(d2),
+            _ => None,
+        };
         match (self.float, other.float) {
             (Some(f1), Some(f2)) => self.float = Some(f1 + f2),
             (Some(f1), None) => self.float = Some(f1 + (other.integer as f64)),
@@ -508,6 +1064,84 @@ impl Commute for TypedSum {
     }
 }
 
+/// Decimal is an exact decimal number, stored as an integer scaled by
+/// `10^scale`.
+#[derive(Clone, Copy, Debug, Default)]
+struct Decimal {
+    value: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Parses a number like `-12.340`, without an exponent.
+    fn parse(sample: &[u8]) -> Option<Decimal> {
+        let (negative, digits) = match sample.first() {
+            Some(&b'-') => (true, &sample[1..]),
+            Some(&b'+') => (false, &sample[1..]),
+            _ => (false, sample),
+        };
+        let mut value: i128 = 0;
+        let mut scale = None;
+        let mut ndigits = 0;
+        for &b in digits {
+            match b {
+                b'.' if scale.is_none() => scale = Some(0),
+                b'0'..=b'9' => {
+                    value = value.checked_mul(10)?
+                                 .checked_add((b - b'0') as i128)?;
+                    scale = scale.map(|s| s + 1);
+                    ndigits += 1;
+                }
+                _ => return None,
+            }
+        }
+        if ndigits == 0 {
+            return None;
+        }
+        Some(Decimal {
+            value: if negative { -value } else { value },
+            scale: scale.unwrap_or(0),
+        })
+    }
+
+    fn rescale(self, scale: u32) -> Option<Decimal> {
+        let mut value = self.value;
+        for _ in self.scale..scale {
+            value = value.checked_mul(10)?;
+        }
+        Some(Decimal { value: value, scale: scale })
+    }
+
+    fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        let scale = cmp::max(self.scale, other.scale);
+        let (a, b) = (self.rescale(scale)?, other.rescale(scale)?);
+        Some(Decimal { value: a.value.checked_add(b.value)?, scale: scale })
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Going through `u128` keeps the magnitude of `i128::MIN` intact.
+        let magnitude =
+            if self.value < 0 {
+                (self.value as u128).wrapping_neg()
+            } else {
+                self.value as u128
+            };
+        let digits = magnitude.to_string();
+        let scale = self.scale as usize;
+        // Pad with zeros so that there is a digit before the point.
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        let sign = if self.value < 0 { "-" } else { "" };
+        if frac.is_empty() {
+            write!(f, "{}{}", sign, int)
+        } else {
+            write!(f, "{}{}.{}", sign, int, frac)
+        }
+    }
+}
+
 /// TypedMinMax keeps track of minimum/maximum values for each possible type
 /// where min/max makes sense.
 #[derive(Clone)]
@@ -553,7 +1187,11 @@ impl TypedMinMax {
         }
     }
 
-    fn show(&self, typ: FieldType) -> Option<(String, String)> {
+    fn show(
+        &self,
+        typ: FieldType,
+        round: Option<u32>,
+    ) -> Option<(String, String)> {
         match typ {
             TNull => None,
             TUnicode | TUnknown => {
@@ -577,7 +1215,8 @@ impl TypedMinMax {
             TFloat => {
                 match (self.floats.min(), self.floats.max()) {
                     (Some(min), Some(max)) => {
-                        Some((min.to_string(), max.to_string()))
+                        Some((format_float(*min, round),
+                              format_float(*max, round)))
                     }
                     _ => None
                 }
@@ -10,7 +10,10 @@ use util;
 static USAGE: &'static str = "
 Outputs CSV data as a table with columns in alignment.
 
-This will not work well if the CSV data contains large fields.
+This will not work well if the CSV data contains large fields. Column widths
+are measured in terminal columns, so wide characters (like CJK characters) stay
+aligned. Tabs and line breaks inside fields are shown as spaces since they
+would otherwise break the alignment.
 
 Note that formatting a table requires buffering all CSV data into memory.
 Therefore, you should use the 'sample' or 'slice' command to trim down large
@@ -22,37 +25,56 @@ Usage:
 table options:
     -w, --width <arg>      The minimum width of each column.
                            [default: 2]
+    -W, --max-width <arg>  The maximum width of each column. Longer fields
+                           are cut and end with an ellipsis. Widths are
+                           measured in terminal columns.
     -p, --pad <arg>        The minimum number of spaces between each column.
                            [default: 2]
     -c, --condense <arg>  Limits the length of each field to the value
                            specified. If the field is UTF-8 encoded, then
                            <arg> refers to the number of code points.
                            Otherwise, it refers to the number of bytes.
+    --rtl-safe             End fields with right-to-left text, like Hebrew
+                           or Arabic, with an invisible left-to-right mark.
+                           This keeps terminals from mixing up the order of
+                           the columns that follow them.
 
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
     flag_width: usize,
+    flag_max_width: Option<usize>,
     flag_pad: usize,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
     flag_condense: Option<usize>,
+    flag_rtl_safe: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    if let Some(max) = args.flag_max_width {
+        if max < args.flag_width {
+            return fail!("--max-width must not be less than --width.");
+        }
+    }
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(true);
+    // Columns are aligned on flush, so flushing every record would defeat
+    // the purpose of this command.
     let wconfig = Config::new(&args.flag_output)
-        .delimiter(Some(Delimiter(b'\t')));
+        .delimiter(Some(Delimiter(b'\t')))
+        .line_buffered(false);
 
     let tw = TabWriter::new(wconfig.io_writer()?)
         .minwidth(args.flag_width)
@@ -63,9 +85,26 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut record = csv::ByteRecord::new();
     while rdr.read_byte_record(&mut record)? {
         wtr.write_record(record.iter().map(|f| {
-            util::condense(Cow::Borrowed(f), args.flag_condense)
+            let f = util::condense(spaces_for_breaks(f), args.flag_condense);
+            let f = match args.flag_max_width {
+                None => f,
+                Some(max) => util::unicode_aware_ellipsis(f, max),
+            };
+            if args.flag_rtl_safe { util::rtl_safe(f) } else { f }
         }))?;
     }
     wtr.flush()?;
     Ok(())
 }
+
+/// Replaces tabs and line breaks in `field` with spaces, since tabs and line
+/// breaks separate the cells of a `TabWriter`.
+fn spaces_for_breaks<'a>(field: &'a [u8]) -> Cow<'a, [u8]> {
+    let is_break = |b: &u8| *b == b'\t' || *b == b'\n' || *b == b'\r';
+    if !field.iter().any(is_break) {
+        return Cow::Borrowed(field);
+    }
+    Cow::Owned(field.iter()
+                    .map(|b| if is_break(b) { b' ' } else { *b })
+                    .collect())
+}
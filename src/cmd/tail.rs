@@ -0,0 +1,75 @@
+use std::collections::VecDeque;
+
+use CliResult;
+use config::{Config, Delimiter};
+use util;
+
+static USAGE: &'static str = "
+Returns the last records of CSV data, like Unix 'tail'.
+
+The header row is not counted. With an index (see 'xsv index'), only the
+returned records are parsed. Otherwise, all of the data is read, but only the
+last <n> records are kept in memory.
+
+Usage:
+    xsv tail [options] [<input>]
+    xsv tail --help
+
+tail options:
+    -l, --len <n>          The number of records to return. [default: 10]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. Otherwise, the first row will always
+                           appear in the output as the header row.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_len: usize,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    if let Some(mut idx) = rconfig.indexed()? {
+        rconfig.write_headers(&mut *idx, &mut wtr)?;
+        let count = idx.count();
+        if count == 0 || args.flag_len == 0 {
+            return Ok(wtr.flush()?);
+        }
+        idx.seek(count.saturating_sub(args.flag_len as u64))?;
+        for r in idx.byte_records() {
+            wtr.write_byte_record(&r?)?;
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    let mut rdr = rconfig.reader()?;
+    rconfig.write_headers(&mut rdr, &mut wtr)?;
+    let mut last = VecDeque::new();
+    for r in rdr.byte_records() {
+        last.push_back(r?);
+        if last.len() > args.flag_len {
+            last.pop_front();
+        }
+    }
+    for r in &last {
+        wtr.write_byte_record(r)?;
+    }
+    Ok(wtr.flush()?)
+}
@@ -0,0 +1,131 @@
+use std::io;
+
+use csv;
+
+use CliResult;
+use config::{self, Config, Delimiter};
+use select::SelectColumns;
+use util;
+
+static USAGE: &'static str = "
+Removes repeated rows from sorted CSV data, like Unix 'uniq'.
+
+Adjacent rows whose selected columns are equal form a group, and only the
+first row of each group is written. Since only adjacent rows are compared,
+the data should be sorted on the selected columns first (e.g., with
+'xsv sort'). In exchange, this uses constant memory, unlike 'xsv frequency'.
+
+  Remove duplicate rows:
+  $ xsv sort data.csv | xsv uniq
+
+  Count the rows of each distinct city:
+  $ xsv sort -s city data.csv | xsv uniq -c -s city
+
+Usage:
+    xsv uniq [options] [<input>]
+    xsv uniq --help
+
+uniq options:
+    -s, --select <arg>     Select the columns to compare rows on. By default,
+                           whole rows are compared.
+                           See 'xsv select --help' for the format details.
+    -c, --count            Prepend a 'count' column with the number of rows
+                           in each group.
+    -D, --repeated         Only write groups with more than one row.
+    -u, --unique           Only write groups with exactly one row.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. (i.e., They are not searched, analyzed,
+                           sliced, etc.)
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_count: bool,
+    flag_repeated: bool,
+    flag_unique: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_repeated && args.flag_unique {
+        return fail!("Only one of --repeated or --unique may be given.");
+    }
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select.clone());
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+
+    if !rconfig.no_headers {
+        if args.flag_count {
+            let mut record = csv::ByteRecord::new();
+            record.push_field(b"count");
+            record.extend(&headers);
+            wtr.write_byte_record(&record)?;
+        } else {
+            wtr.write_byte_record(&headers)?;
+        }
+    }
+
+    // The first row of the current group along with its size.
+    let mut group: Option<(csv::ByteRecord, u64)> = None;
+    let mut row = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut row)? {
+        if let Some((ref first, ref mut count)) = group {
+            if sel.select(first).eq(sel.select(&row)) {
+                *count += 1;
+                continue;
+            }
+        }
+        if let Some((first, count)) = group.take() {
+            args.write_group(&mut wtr, &first, count)?;
+        }
+        group = Some((row.clone(), 1));
+    }
+    if let Some((first, count)) = group {
+        args.write_group(&mut wtr, &first, count)?;
+    }
+    Ok(wtr.flush()?)
+}
+
+impl Args {
+    fn write_group<W: io::Write>(
+        &self,
+        wtr: &mut config::Writer<W>,
+        first: &csv::ByteRecord,
+        count: u64,
+    ) -> CliResult<()> {
+        if (self.flag_repeated && count == 1)
+                || (self.flag_unique && count > 1) {
+            return Ok(());
+        }
+        if self.flag_count {
+            let count = count.to_string();
+            let mut record = csv::ByteRecord::new();
+            record.push_field(count.as_bytes());
+            record.extend(first);
+            wtr.write_byte_record(&record)?;
+        } else {
+            wtr.write_byte_record(first)?;
+        }
+        Ok(())
+    }
+}
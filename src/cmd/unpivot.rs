@@ -0,0 +1,108 @@
+use csv;
+
+use CliResult;
+use config::{Config, Delimiter};
+use select::SelectColumns;
+use util;
+
+static USAGE: &'static str = "
+Unpivots CSV data from a wide format into a long format. This is also known
+as melting.
+
+For each input record, one record is written for every melted column. Each
+output record contains the --id columns followed by a 'variable' column
+holding the name of the melted column and a 'value' column holding its value.
+By default, every column that isn't an --id column is melted. Use
+the --value-cols option to only melt some of them.
+
+For example, given this data:
+
+    city,2000,2010
+    Boston,589,617
+    Paris,,2243
+
+running 'xsv unpivot --id city' gives:
+
+    city,variable,value
+    Boston,2000,589
+    Boston,2010,617
+    Paris,2000,
+    Paris,2010,2243
+
+Usage:
+    xsv unpivot [options] --id <cols> [<input>]
+    xsv unpivot --help
+
+unpivot options:
+    --id <cols>            The columns to keep in every output record.
+                           See 'xsv select --help' for the format details.
+    --value-cols <cols>    The columns to melt. By default, all columns
+                           that are not --id columns are melted.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. The 'variable' column then contains
+                           the 1-based index of each melted column.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+
+    See 'xsv --help' for the options that every command accepts.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_id: SelectColumns,
+    flag_value_cols: Option<SelectColumns>,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let use_names = !args.flag_no_headers;
+    let id_sel = args.flag_id.selection(&headers, use_names)?;
+    let value_cols: Vec<usize> = match args.flag_value_cols {
+        Some(ref sel) => sel.selection(&headers, use_names)?.to_vec(),
+        None => (0..headers.len()).filter(|i| !id_sel.contains(i)).collect(),
+    };
+    let variables: Vec<Vec<u8>> = value_cols.iter().map(|&i| {
+        if use_names {
+            headers[i].to_vec()
+        } else {
+            (i + 1).to_string().into_bytes()
+        }
+    }).collect();
+
+    if use_names {
+        let mut record = csv::ByteRecord::new();
+        record.extend(id_sel.select(&headers));
+        record.push_field(b"variable");
+        record.push_field(b"value");
+        wtr.write_byte_record(&record)?;
+    }
+    let mut row = csv::ByteRecord::new();
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut row)? {
+        for (&i, variable) in value_cols.iter().zip(&variables) {
+            record.clear();
+            record.extend(id_sel.select(&row));
+            record.push_field(variable);
+            record.push_field(&row[i]);
+            wtr.write_byte_record(&record)?;
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
@@ -1,14 +1,21 @@
 #[allow(deprecated, unused_imports)]
 use std::ascii::AsciiExt;
 use std::borrow::ToOwned;
+use std::cell::RefCell;
+use std::cmp;
 use std::env;
 use std::fs;
 use std::io::{self, Read};
-use std::ops::Deref;
+use std::iter;
+use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
+use std::ptr;
+use std::rc::Rc;
+#[allow(deprecated)]
+use std::sync::{Mutex, Once, ONCE_INIT};
 
 use csv;
-use index::Indexed;
+use index::{Indexed, MemoryIndex};
 use serde::de::{Deserializer, Deserialize, Error};
 
 use CliResult;
@@ -60,14 +67,22 @@ pub struct Config {
     idx_path: Option<PathBuf>,
     select_columns: Option<SelectColumns>,
     delimiter: u8,
+    delimiter_set: bool,
     pub no_headers: bool,
     flexible: bool,
     terminator: csv::Terminator,
+    read_terminator: csv::Terminator,
     quote: u8,
     quote_style: csv::QuoteStyle,
     double_quote: bool,
     escape: Option<u8>,
     quoting: bool,
+    final_terminator: bool,
+    head: Option<u64>,
+    zero_based: bool,
+    line_buffered: bool,
+    trim_headers: bool,
+    uniquify_headers: bool,
 }
 
 impl Config {
@@ -86,25 +101,35 @@ impl Config {
                 (Some(path), delim)
             }
         };
+        let stdout = path.is_none();
         Config {
             path: path,
             idx_path: None,
             select_columns: None,
             delimiter: delim,
+            delimiter_set: false,
             no_headers: false,
             flexible: false,
             terminator: csv::Terminator::Any(b'\n'),
+            read_terminator: csv::Terminator::CRLF,
             quote: b'"',
             quote_style: csv::QuoteStyle::Necessary,
             double_quote: true,
             escape: None,
             quoting: true,
+            final_terminator: !util::no_final_newline(),
+            head: None,
+            zero_based: false,
+            line_buffered: stdout && util::line_buffered(),
+            trim_headers: util::trim_headers(),
+            uniquify_headers: util::uniquify_headers(),
         }
     }
 
     pub fn delimiter(mut self, d: Option<Delimiter>) -> Config {
         if let Some(d) = d {
             self.delimiter = d.as_byte();
+            self.delimiter_set = true;
         }
         self
     }
@@ -136,6 +161,14 @@ impl Config {
         self
     }
 
+    /// Sets the record terminator used when reading CSV data.
+    ///
+    /// The default treats any of `\r`, `\n` or `\r\n` as a terminator.
+    pub fn read_terminator(mut self, term: csv::Terminator) -> Config {
+        self.read_terminator = term;
+        self
+    }
+
     pub fn quote(mut self, quote: u8) -> Config {
         self.quote = quote;
         self
@@ -161,11 +194,40 @@ impl Config {
         self
     }
 
+    /// Limit the number of records read to at most `n`.
+    ///
+    /// This is applied by commands through `limit_records` and
+    /// `limit_count`, since the readers returned by this config are not
+    /// themselves limited.
+    pub fn head(mut self, n: Option<u64>) -> Config {
+        self.head = n;
+        self
+    }
+
+    /// When enabled, numeric indices in the selection given to `select` are
+    /// 0-based instead of 1-based.
+    pub fn zero_based(mut self, yes: bool) -> Config {
+        self.zero_based = yes;
+        self
+    }
+
     pub fn select(mut self, sel_cols: SelectColumns) -> Config {
         self.select_columns = Some(sel_cols);
         self
     }
 
+    /// Limit the given record iterator to the number of records set with
+    /// `head`, if any.
+    pub fn limit_records<I: Iterator>(&self, it: I) -> iter::Take<I> {
+        it.take(self.head.map_or(usize::MAX, |n| n as usize))
+    }
+
+    /// Limit a record count (e.g., from an index) to the number of records
+    /// set with `head`, if any.
+    pub fn limit_count(&self, count: u64) -> u64 {
+        self.head.map_or(count, |n| cmp::min(n, count))
+    }
+
     pub fn is_std(&self) -> bool {
         self.path.is_none()
     }
@@ -177,12 +239,13 @@ impl Config {
         match self.select_columns {
             None => Err("Config has no 'SelectColums'. Did you call \
                          Config::select?".to_owned()),
-            Some(ref sel) => sel.selection(first_record, !self.no_headers),
+            Some(ref sel) => sel.selection_with_base(
+                first_record, !self.no_headers, self.zero_based),
         }
     }
 
     pub fn write_headers<R: io::Read, W: io::Write>
-                        (&self, r: &mut csv::Reader<R>, w: &mut csv::Writer<W>)
+                        (&self, r: &mut csv::Reader<R>, w: &mut Writer<W>)
                         -> csv::Result<()> {
         if !self.no_headers {
             let r = r.byte_headers()?;
@@ -193,9 +256,29 @@ impl Config {
         Ok(())
     }
 
+    /// Whether writers flush their output after every record. This is
+    /// enabled for <stdout> by the `--line-buffered` flag.
+    pub fn line_buffered(mut self, yes: bool) -> Config {
+        self.line_buffered = yes;
+        self
+    }
+
     pub fn writer(&self)
-                 -> io::Result<csv::Writer<Box<io::Write+'static>>> {
-        Ok(self.from_writer(self.io_writer()?))
+                 -> io::Result<Writer<Box<io::Write+'static>>> {
+        let wtr = self.io_writer()?;
+        if self.final_terminator {
+            return Ok(self.from_writer(wtr));
+        }
+        let term = match self.terminator {
+            csv::Terminator::CRLF => b"\r\n".to_vec(),
+            csv::Terminator::Any(b) => vec![b],
+            _ => unreachable!(),
+        };
+        Ok(self.from_writer(Box::new(NoFinalTerminator {
+            wtr: wtr,
+            term: term,
+            pending: vec![],
+        })))
     }
 
     pub fn reader(&self)
@@ -260,8 +343,35 @@ impl Config {
         }
     }
 
+    /// Builds an index of the CSV data in memory.
+    ///
+    /// This permits random access for a single invocation when no index file
+    /// exists, at the cost of reading all of the CSV data once. If the data
+    /// is on `<stdin>`, then `None` is returned.
+    pub fn memory_index(&self) -> CliResult<Option<MemoryIndex>> {
+        if self.is_std() {
+            return Ok(None);
+        }
+        Ok(Some(MemoryIndex::create(&mut self.reader_file()?)?))
+    }
+
+    /// Composes a reader for this CSV data with an index built by
+    /// `memory_index`.
+    pub fn indexed_in_memory(&self, idx: MemoryIndex)
+                            -> CliResult<Indexed<fs::File,
+                                                 io::Cursor<MemoryIndex>>> {
+        Indexed::open(self.reader_file()?, io::Cursor::new(idx))
+    }
+
     pub fn io_reader(&self) -> io::Result<Box<io::Read+'static>> {
         Ok(match self.path {
+                None if self.should_sniff() => {
+                    let (sample, truncated) = read_sample(io::stdin())?;
+                    let delim = self.report_sniffed(
+                        util::sniff_delimiter(&sample, truncated));
+                    sniffed().lock().unwrap().push((None, delim));
+                    Box::new(io::Cursor::new(sample).chain(io::stdin()))
+                }
                 None => Box::new(io::stdin()),
                 Some(ref p) => {
                     match fs::File::open(p){
@@ -279,15 +389,111 @@ impl Config {
             })
     }
 
+    /// The `--tsv` flag takes precedence over any other delimiter, while a
+    /// delimiter guessed because of `--sniff` only replaces one inferred from
+    /// the file extension.
+    ///
+    /// For `<stdin>`, a delimiter is only guessed once `io_reader` is called.
+    pub fn read_delimiter(&self) -> u8 {
+        if util::tsv_input() {
+            b'\t'
+        } else if self.should_sniff() {
+            self.sniffed_delimiter().unwrap_or(self.delimiter)
+        } else {
+            self.delimiter
+        }
+    }
+
+    fn should_sniff(&self) -> bool {
+        util::sniff() && !util::tsv_input() && !self.delimiter_set
+    }
+
+    /// Returns the delimiter guessed for this CSV data.
+    ///
+    /// The guess is made only once for each input, since many readers may be
+    /// created for it. The guess for `<stdin>` is made by `io_reader`.
+    fn sniffed_delimiter(&self) -> Option<u8> {
+        let mut sniffed = sniffed().lock().unwrap();
+        if let Some(&(_, delim)) =
+                sniffed.iter().find(|s| s.0 == self.path) {
+            return delim;
+        }
+        let sample = match self.path {
+            None => return None,
+            Some(ref p) => match fs::File::open(p) {
+                Err(_) => return None,
+                Ok(f) => read_sample(f),
+            },
+        };
+        let delim = match sample {
+            Err(_) => None,
+            Ok((sample, truncated)) => {
+                self.report_sniffed(util::sniff_delimiter(&sample, truncated))
+            }
+        };
+        sniffed.push((self.path.clone(), delim));
+        delim
+    }
+
+    fn report_sniffed(&self, delim: Option<u8>) -> Option<u8> {
+        let name = match self.path {
+            None => "<stdin>".to_owned(),
+            Some(ref p) => p.display().to_string(),
+        };
+        match delim {
+            None => werr!("{}: could not sniff the delimiter", name),
+            Some(d) => werr!("{}: sniffed delimiter {:?}", name, d as char),
+        }
+        delim
+    }
+
+    /// The `--tsv` and `--tsv-output` flags take precedence over any other
+    /// delimiter.
+    fn write_delimiter(&self) -> u8 {
+        if util::tsv_output() { b'\t' } else { self.delimiter }
+    }
+
     pub fn from_reader<R: Read>(&self, rdr: R) -> csv::Reader<R> {
-        csv::ReaderBuilder::new()
+        let trim =
+            if self.trim_headers { csv::Trim::Headers } else { csv::Trim::None };
+        let mut rdr = csv::ReaderBuilder::new()
             .flexible(self.flexible)
-            .delimiter(self.delimiter)
+            .trim(trim)
+            .delimiter(self.read_delimiter())
+            .terminator(self.read_terminator)
             .has_headers(!self.no_headers)
             .quote(self.quote)
             .quoting(self.quoting)
             .escape(self.escape)
-            .from_reader(rdr)
+            .from_reader(rdr);
+        // The header row is read right away so that every command sees the
+        // unique names. If reading it fails, the command gets the error when
+        // it reads the header row itself.
+        if self.uniquify_headers && !self.no_headers {
+            let headers = rdr.byte_headers().map(util::unique_headers);
+            if let Ok(headers) = headers {
+                rdr.set_byte_headers(headers);
+            }
+        }
+        rdr
+    }
+
+    /// Returns a reader that also gives access to the exact bytes that each
+    /// record was parsed from.
+    pub fn raw_reader(&self)
+                     -> io::Result<RawReader<Box<io::Read+'static>>> {
+        let buf = Rc::new(RefCell::new(RawBuf::default()));
+        let recorder = Recorder {
+            rdr: self.io_reader()?,
+            buf: buf.clone(),
+            eof: false,
+        };
+        Ok(RawReader {
+            rdr: self.from_reader(recorder),
+            buf: buf,
+            raw: vec![],
+            skipped: 0,
+        })
     }
 
     pub fn io_writer(&self) -> io::Result<Box<io::Write+'static>> {
@@ -297,16 +503,246 @@ impl Config {
         })
     }
 
-    pub fn from_writer<W: io::Write>(&self, wtr: W) -> csv::Writer<W> {
-        csv::WriterBuilder::new()
+    pub fn from_writer<W: io::Write>(&self, wtr: W) -> Writer<W> {
+        let wtr = csv::WriterBuilder::new()
             .flexible(self.flexible)
-            .delimiter(self.delimiter)
+            .delimiter(self.write_delimiter())
             .terminator(self.terminator)
             .quote(self.quote)
             .quote_style(self.quote_style)
             .double_quote(self.double_quote)
             .escape(self.escape.unwrap_or(b'\\'))
             .buffer_capacity(32 * (1<<10))
-            .from_writer(wtr)
+            .from_writer(wtr);
+        Writer { wtr: wtr, line_buffered: self.line_buffered }
+    }
+
+    /// Returns true if raw output should be flushed after every record.
+    pub fn is_line_buffered(&self) -> bool {
+        self.line_buffered
+    }
+}
+
+type Sniffed = Mutex<Vec<(Option<PathBuf>, Option<u8>)>>;
+
+/// Returns the delimiters guessed for each input because of `--sniff`.
+///
+/// The list is created on first use, since a `Mutex` can't be built in a
+/// static.
+#[allow(deprecated)]
+fn sniffed() -> &'static Sniffed {
+    static INIT: Once = ONCE_INIT;
+    static mut SNIFFED: *const Sniffed = ptr::null();
+    unsafe {
+        INIT.call_once(|| {
+            SNIFFED = Box::into_raw(Box::new(Mutex::new(vec![])));
+        });
+        &*SNIFFED
+    }
+}
+
+/// The number of bytes used to guess the delimiter of CSV data.
+const SNIFF_SAMPLE_SIZE: usize = 16 * (1<<10);
+
+/// Reads the beginning of `rdr` to guess its delimiter, along with whether
+/// more data follows it.
+fn read_sample<R: io::Read>(rdr: R) -> io::Result<(Vec<u8>, bool)> {
+    let mut sample = vec![];
+    rdr.take(SNIFF_SAMPLE_SIZE as u64 + 1).read_to_end(&mut sample)?;
+    let truncated = sample.len() > SNIFF_SAMPLE_SIZE;
+    Ok((sample, truncated))
+}
+
+/// Writer is a CSV writer that can flush its output after every record.
+///
+/// Flushing after every record lets a live consumer, like a pager, see each
+/// record as soon as it's written, at the expense of throughput. Everything
+/// besides writing records is done by the wrapped `csv::Writer`.
+pub struct Writer<W: io::Write> {
+    wtr: csv::Writer<W>,
+    line_buffered: bool,
+}
+
+impl<W: io::Write> Writer<W> {
+    pub fn write_record<I, T>(&mut self, record: I) -> csv::Result<()>
+        where I: IntoIterator<Item=T>, T: AsRef<[u8]>
+    {
+        self.wtr.write_record(record)?;
+        self.flush_record()
+    }
+
+    pub fn write_byte_record(
+        &mut self,
+        record: &csv::ByteRecord,
+    ) -> csv::Result<()> {
+        self.wtr.write_byte_record(record)?;
+        self.flush_record()
+    }
+
+    fn flush_record(&mut self) -> csv::Result<()> {
+        if self.line_buffered {
+            self.wtr.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: io::Write> Deref for Writer<W> {
+    type Target = csv::Writer<W>;
+
+    fn deref(&self) -> &csv::Writer<W> {
+        &self.wtr
+    }
+}
+
+impl<W: io::Write> DerefMut for Writer<W> {
+    fn deref_mut(&mut self) -> &mut csv::Writer<W> {
+        &mut self.wtr
+    }
+}
+
+/// NoFinalTerminator is a writer that never writes the last record
+/// terminator.
+///
+/// It does this by holding back a terminator written at the end of each
+/// buffer. The held back bytes are written once more data follows, so only
+/// the terminator at the very end of the output is dropped.
+struct NoFinalTerminator<W> {
+    wtr: W,
+    term: Vec<u8>,
+    pending: Vec<u8>,
+}
+
+impl<W: io::Write> io::Write for NoFinalTerminator<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        // Keep a complete terminator, or the start of one that may be
+        // completed by the next write.
+        let keep = (1..self.term.len() + 1)
+            .rev()
+            .find(|&k| self.pending.ends_with(&self.term[..k]))
+            .unwrap_or(0);
+        let end = self.pending.len() - keep;
+        self.wtr.write_all(&self.pending[..end])?;
+        self.pending.drain(..end);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.wtr.flush()
+    }
+}
+
+/// RawReader reads CSV records along with the bytes they were parsed from.
+///
+/// The bytes of a record include its terminator and any empty lines before
+/// it, so that writing the bytes of every record reproduces the input.
+pub struct RawReader<R> {
+    rdr: csv::Reader<Recorder<R>>,
+    buf: Rc<RefCell<RawBuf>>,
+    raw: Vec<u8>,
+    /// The number of bytes that were never given to the CSV parser.
+    skipped: u64,
+}
+
+impl<R: io::Read> RawReader<R> {
+    /// Returns the header row.
+    ///
+    /// When the data has headers, their bytes are available from `raw`
+    /// afterwards. Otherwise, the first record is still returned by
+    /// `read_byte_record`.
+    pub fn byte_headers(&mut self) -> csv::Result<csv::ByteRecord> {
+        let headers = self.rdr.byte_headers()?.clone();
+        if self.rdr.has_headers() {
+            self.take_raw();
+        }
+        Ok(headers)
+    }
+
+    pub fn read_byte_record(
+        &mut self,
+        record: &mut csv::ByteRecord,
+    ) -> csv::Result<bool> {
+        if self.rdr.read_byte_record(record)? {
+            self.take_raw();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Returns the bytes of the last record (or header row) that was read.
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    fn take_raw(&mut self) {
+        let mut buf = self.buf.borrow_mut();
+        let pos = self.rdr.position().byte() + self.skipped;
+        let mut end = (pos - buf.offset) as usize;
+        // The CSV parser stops at the `\r` of a `\r\n` terminator, so the
+        // `\n` is added here. If the parser hasn't seen it yet, then it is
+        // skipped so that it doesn't show up before the next record.
+        if buf.bytes[..end].ends_with(b"\r")
+                && buf.bytes.get(end) == Some(&b'\n') {
+            end += 1;
+            if pos + 1 > buf.given {
+                buf.given = pos + 1;
+                self.skipped += 1;
+            }
+        }
+        self.raw.clear();
+        self.raw.extend(buf.bytes.drain(..end));
+        buf.offset += end as u64;
+    }
+}
+
+#[derive(Default)]
+struct RawBuf {
+    /// The bytes read from the underlying reader that are not yet part of a
+    /// record returned by a `RawReader`.
+    bytes: Vec<u8>,
+    /// The position of `bytes[0]` in the input.
+    offset: u64,
+    /// The position in the input up to which bytes were handed to the CSV
+    /// parser.
+    given: u64,
+}
+
+/// Recorder is a reader that keeps a copy of everything the CSV parser
+/// reads.
+///
+/// It always reads one byte further than the parser, so that the end of a
+/// record terminator can be found without waiting for the next record.
+struct Recorder<R> {
+    rdr: R,
+    buf: Rc<RefCell<RawBuf>>,
+    eof: bool,
+}
+
+impl<R: io::Read> io::Read for Recorder<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let mut buf = self.buf.borrow_mut();
+        let mut chunk = [0; 8 * (1<<10)];
+        loop {
+            let given = (buf.given - buf.offset) as usize;
+            if self.eof || buf.bytes.len() - given > 1 {
+                break;
+            }
+            let n = self.rdr.read(&mut chunk)?;
+            if n == 0 {
+                self.eof = true;
+            }
+            buf.bytes.extend_from_slice(&chunk[..n]);
+        }
+        let given = (buf.given - buf.offset) as usize;
+        let mut avail = buf.bytes.len() - given;
+        if !self.eof {
+            avail -= 1;
+        }
+        let n = cmp::min(avail, out.len());
+        out[..n].copy_from_slice(&buf.bytes[given..given + n]);
+        buf.given += n as u64;
+        Ok(n)
     }
 }
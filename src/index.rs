@@ -1,5 +1,6 @@
 use std::io;
 use std::ops;
+use std::sync::Arc;
 
 use csv;
 use csv_index::RandomAccessSimple;
@@ -59,3 +60,26 @@ impl<R: io::Read + io::Seek, I: io::Read + io::Seek> Indexed<R, I> {
         Ok(())
     }
 }
+
+/// MemoryIndex is a simple random access index that lives in memory.
+///
+/// It is built for a single invocation when no index file exists but random
+/// access is still useful. It is cheap to clone, so that it can be shared
+/// between threads.
+#[derive(Clone, Debug)]
+pub struct MemoryIndex(Arc<Vec<u8>>);
+
+impl MemoryIndex {
+    /// Creates an index by reading all of the CSV data in `csv_rdr`.
+    pub fn create<R: io::Read>(
+        csv_rdr: &mut csv::Reader<R>,
+    ) -> CliResult<MemoryIndex> {
+        let mut buf = vec![];
+        RandomAccessSimple::create(csv_rdr, &mut buf)?;
+        Ok(MemoryIndex(Arc::new(buf)))
+    }
+}
+
+impl AsRef<[u8]> for MemoryIndex {
+    fn as_ref(&self) -> &[u8] { &self.0 }
+}
@@ -4,15 +4,19 @@ extern crate csv;
 extern crate csv_index;
 extern crate docopt;
 extern crate filetime;
+extern crate libc;
 extern crate num_cpus;
 extern crate rand;
 extern crate regex;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+extern crate serde_json;
 extern crate stats;
+extern crate strsim;
 extern crate tabwriter;
 extern crate threadpool;
+extern crate unicode_width;
 
 use std::borrow::ToOwned;
 use std::env;
@@ -45,25 +49,36 @@ macro_rules! command_list {
 "
     cat         Concatenate by row or column
     count       Count records
+    describe    Show a quick overview of CSV data
+    diff        Compare two CSV files by key
+    explode     Expand a JSON array column into rows
     fixlengths  Makes all records have same length
     flatten     Show one field per line
     fmt         Format CSV output (change field delimiter)
     frequency   Show frequency tables
+    hash        Compute a fingerprint of CSV data
+    head        Show the first records of CSV data
     headers     Show header names
     help        Show this usage message.
     index       Create CSV index for faster access
     input       Read CSV data with special quoting rules
     join        Join CSV files
     partition   Partition CSV data based on a column value
+    pivot       Pivot CSV data from long to wide format
     sample      Randomly sample CSV data
+    schema      Infer the type of each column
     reverse     Reverse rows of CSV data
     search      Search CSV data with regexes
     select      Select columns from CSV
     slice       Slice records from CSV
     sort        Sort CSV data
+    sparkline   Draw the distribution of numeric columns
     split       Split CSV data into many files
     stats       Compute basic statistics
     table       Align CSV data into columns
+    tail        Show the last records of CSV data
+    uniq        Remove repeated rows from sorted CSV data
+    unpivot     Unpivot CSV data from wide to long format
 "
     )
 }
@@ -85,6 +100,26 @@ Options:
     <command> -h  Display the command help message
     --version     Print version info and exit
 
+Every command also accepts '--tsv' to read and write tab-delimited data, and
+'--tsv-output' to only write tab-delimited data. Both take precedence over
+file extensions and delimiter options. Commands also accept '--sniff' to guess
+the delimiter of their input among ',', ';', tab and '|', unless a delimiter
+is given with '--delimiter'.
+
+Output to <stdout> is buffered in large blocks. With '--line-buffered', it is
+flushed after every record instead, so that a live consumer like 'less' sees
+each record right away. This makes writing large outputs noticeably slower.
+
+With '--trim-headers', leading and trailing whitespace is removed from the
+names in the header row as soon as it is read, so that e.g. a column named
+' name ' can be selected as 'name'. Data rows are never trimmed. Similarly, with
+'--uniquify-headers', every repeated name in the header row gets a suffix
+like '_2' or '_3', so that two columns named 'id' can be selected as 'id' and
+'id_2'.
+
+With '--no-final-newline', no record terminator is written after the last
+record of the CSV output, since some tools choke on a trailing newline.
+
 Commands:", command_list!());
 
 #[derive(Deserialize)]
@@ -142,25 +177,36 @@ Please choose one of the following commands:",
 enum Command {
     Cat,
     Count,
+    Describe,
+    Diff,
+    Explode,
     FixLengths,
     Flatten,
     Fmt,
     Frequency,
+    Hash,
+    Head,
     Headers,
     Help,
     Index,
     Input,
     Join,
     Partition,
+    Pivot,
     Reverse,
     Sample,
+    Schema,
     Search,
     Select,
     Slice,
     Sort,
+    Sparkline,
     Split,
     Stats,
     Table,
+    Tail,
+    Uniq,
+    Unpivot,
 }
 
 impl Command {
@@ -177,25 +223,36 @@ impl Command {
         match self {
             Command::Cat => cmd::cat::run(argv),
             Command::Count => cmd::count::run(argv),
+            Command::Describe => cmd::describe::run(argv),
+            Command::Diff => cmd::diff::run(argv),
+            Command::Explode => cmd::explode::run(argv),
             Command::FixLengths => cmd::fixlengths::run(argv),
             Command::Flatten => cmd::flatten::run(argv),
             Command::Fmt => cmd::fmt::run(argv),
             Command::Frequency => cmd::frequency::run(argv),
+            Command::Hash => cmd::hash::run(argv),
+            Command::Head => cmd::head::run(argv),
             Command::Headers => cmd::headers::run(argv),
             Command::Help => { wout!("{}", USAGE); Ok(()) }
             Command::Index => cmd::index::run(argv),
             Command::Input => cmd::input::run(argv),
             Command::Join => cmd::join::run(argv),
             Command::Partition => cmd::partition::run(argv),
+            Command::Pivot => cmd::pivot::run(argv),
             Command::Reverse => cmd::reverse::run(argv),
             Command::Sample => cmd::sample::run(argv),
+            Command::Schema => cmd::schema::run(argv),
             Command::Search => cmd::search::run(argv),
             Command::Select => cmd::select::run(argv),
             Command::Slice => cmd::slice::run(argv),
             Command::Sort => cmd::sort::run(argv),
+            Command::Sparkline => cmd::sparkline::run(argv),
             Command::Split => cmd::split::run(argv),
             Command::Stats => cmd::stats::run(argv),
             Command::Table => cmd::table::run(argv),
+            Command::Tail => cmd::tail::run(argv),
+            Command::Uniq => cmd::uniq::run(argv),
+            Command::Unpivot => cmd::unpivot::run(argv),
         }
     }
 }
@@ -9,10 +9,12 @@ use std::str::FromStr;
 use csv;
 use serde::de::{Deserializer, Deserialize, Error};
 
-#[derive(Clone)]
+/// The default selects every column.
+#[derive(Clone, Default)]
 pub struct SelectColumns {
     selectors: Vec<Selector>,
     invert: bool,
+    excluded: Vec<SelectColumns>,
 }
 
 impl SelectColumns {
@@ -27,26 +29,69 @@ impl SelectColumns {
         Ok(SelectColumns {
             selectors: SelectorParser::new(s).parse()?,
             invert: invert,
+            excluded: vec![],
         })
     }
 
+    /// Drops the columns selected by `excluded` from this selection, while
+    /// keeping the order of the other columns.
+    ///
+    /// `excluded` is resolved exactly like any other selection, so it may
+    /// itself be inverted with a leading `!`.
+    pub fn exclude(mut self, excluded: SelectColumns) -> SelectColumns {
+        self.excluded.push(excluded);
+        self
+    }
+
     pub fn selection(
         &self,
         first_record: &csv::ByteRecord,
         use_names: bool,
     ) -> Result<Selection, String> {
+        self.selection_with_base(first_record, use_names, false)
+    }
+
+    /// Like `selection`, but numeric indices are interpreted as 0-based
+    /// when `zero_based` is set. (They are 1-based otherwise.)
+    pub fn selection_with_base(
+        &self,
+        first_record: &csv::ByteRecord,
+        use_names: bool,
+        zero_based: bool,
+    ) -> Result<Selection, String> {
+        let mut map = self.indices(first_record, use_names, zero_based)?;
+        if !self.excluded.is_empty() {
+            let mut drop = HashSet::new();
+            for excluded in &self.excluded {
+                let sel = excluded.selection_with_base(
+                    first_record, use_names, zero_based)?;
+                drop.extend(sel.iter().cloned());
+            }
+            map.retain(|i| !drop.contains(i));
+        }
+        Ok(Selection(map))
+    }
+
+    /// The indices selected by `self.selectors`, after inversion but before
+    /// removing the excluded columns.
+    fn indices(
+        &self,
+        first_record: &csv::ByteRecord,
+        use_names: bool,
+        zero_based: bool,
+    ) -> Result<Vec<usize>, String> {
         if self.selectors.is_empty() {
-            return Ok(Selection(if self.invert {
+            return Ok(if self.invert {
                 // Inverting everything means we get nothing.
                 vec![]
             } else {
                 (0..first_record.len()).collect()
-            }));
+            });
         }
 
         let mut map = vec![];
         for sel in &self.selectors {
-            let idxs = sel.indices(first_record, use_names);
+            let idxs = sel.indices(first_record, use_names, zero_based);
             map.extend(idxs?.into_iter());
         }
         if self.invert {
@@ -57,9 +102,9 @@ impl SelectColumns {
                     map.push(i);
                 }
             }
-            return Ok(Selection(map));
+            return Ok(map);
         }
-        Ok(Selection(map))
+        Ok(map)
     }
 }
 
@@ -242,26 +287,24 @@ impl Selector {
         &self,
         first_record: &csv::ByteRecord,
         use_names: bool,
+        zero_based: bool,
     ) -> Result<Vec<usize>, String> {
         match *self {
             Selector::One(ref sel) => {
-                sel.index(first_record, use_names).map(|i| vec![i])
+                sel.index(first_record, use_names, zero_based).map(|i| vec![i])
             }
             Selector::Range(ref sel1, ref sel2) => {
-                let i1 = sel1.index(first_record, use_names)?;
-                let i2 = sel2.index(first_record, use_names)?;
+                let i1 = sel1.index(first_record, use_names, zero_based)?;
+                let i2 = sel2.index(first_record, use_names, zero_based)?;
+                // Only open ends get this far when there are no fields, and
+                // they don't select anything then.
+                if first_record.is_empty() {
+                    return Ok(vec![]);
+                }
                 Ok(match i1.cmp(&i2) {
                     Ordering::Equal => vec!(i1),
                     Ordering::Less => (i1..(i2 + 1)).collect(),
-                    Ordering::Greater => {
-                        let mut inds = vec![];
-                        let mut i = i1 + 1;
-                        while i > i2 {
-                            i -= 1;
-                            inds.push(i);
-                        }
-                        inds
-                    }
+                    Ordering::Greater => (i2..(i1 + 1)).rev().collect(),
                 })
             }
         }
@@ -273,6 +316,7 @@ impl OneSelector {
         &self,
         first_record: &csv::ByteRecord,
         use_names: bool,
+        zero_based: bool,
     ) -> Result<usize, String> {
         match *self {
             OneSelector::Start => Ok(0),
@@ -283,6 +327,15 @@ impl OneSelector {
                     first_record.len() - 1
                 }
             ),
+            OneSelector::Index(i) if zero_based => {
+                if i >= first_record.len() {
+                    Err(format!("Selector index {} is out of \
+                                 bounds. Index must be >= 0 \
+                                 and < {}.", i, first_record.len()))
+                } else {
+                    Ok(i)
+                }
+            }
             OneSelector::Index(i) => {
                 if i < 1 || i > first_record.len() {
                     Err(format!("Selector index {} is out of \
@@ -1,23 +1,45 @@
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::hash::Hasher;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::str;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
 use std::time;
 
+use byteorder::{ByteOrder, LittleEndian};
 use csv;
 use docopt::Docopt;
+use libc;
 use num_cpus;
+use rand::{self, SeedableRng};
+use rand::rngs::StdRng;
 use serde::de::{Deserializer, Deserialize, DeserializeOwned, Error};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use CliResult;
-use config::{Config, Delimiter};
+use config::{Config, Delimiter, Writer};
 
 pub fn num_cpus() -> usize {
     num_cpus::get()
 }
 
+/// Returns a random number generator, which is seeded with `seed` when it
+/// is given so that its output is reproducible.
+pub fn acquire_rng(seed: Option<usize>) -> StdRng {
+    match seed {
+        None => StdRng::from_rng(rand::thread_rng()).unwrap(),
+        Some(seed) => {
+            let mut buf = [0u8; 32];
+            LittleEndian::write_u64(&mut buf, seed as u64);
+            SeedableRng::from_seed(buf)
+        }
+    }
+}
+
 pub fn version() -> String {
     let (maj, min, pat) = (
         option_env!("CARGO_PKG_VERSION_MAJOR"),
@@ -31,8 +53,17 @@ pub fn version() -> String {
     }
 }
 
+static TSV_INPUT: AtomicBool = AtomicBool::new(false);
+static TSV_OUTPUT: AtomicBool = AtomicBool::new(false);
+static SNIFF: AtomicBool = AtomicBool::new(false);
+static LINE_BUFFERED: AtomicBool = AtomicBool::new(false);
+static TRIM_HEADERS: AtomicBool = AtomicBool::new(false);
+static UNIQUIFY_HEADERS: AtomicBool = AtomicBool::new(false);
+static NO_FINAL_NEWLINE: AtomicBool = AtomicBool::new(false);
+
 pub fn get_args<T>(usage: &str, argv: &[&str]) -> CliResult<T>
         where T: DeserializeOwned {
+    let argv = strip_global_flags(usage, argv);
     Docopt::new(usage)
            .and_then(|d| d.argv(argv.iter().map(|&x| x))
                           .version(Some(version()))
@@ -40,6 +71,210 @@ pub fn get_args<T>(usage: &str, argv: &[&str]) -> CliResult<T>
            .map_err(From::from)
 }
 
+/// Removes the `--tsv`, `--tsv-output`, `--sniff`, `--line-buffered`,
+/// `--trim-headers`, `--uniquify-headers` and `--no-final-newline` flags,
+/// which every command accepts, from `argv` and records whether they were
+/// given.
+///
+/// Flags after a `--` argument are left alone, as is the value of an option
+/// from `usage` that takes one, so that e.g. `--source --sniff` names a
+/// column `--sniff`.
+fn strip_global_flags<'a>(usage: &str, argv: &[&'a str]) -> Vec<&'a str> {
+    let opts = options_with_values(usage);
+    let mut args = Vec::with_capacity(argv.len());
+    let mut it = argv.iter();
+    while let Some(&arg) = it.next() {
+        if takes_value(arg, &opts) {
+            args.push(arg);
+            args.extend(it.next());
+            continue;
+        }
+        match arg {
+            "--tsv" => {
+                TSV_INPUT.store(true, Ordering::SeqCst);
+                TSV_OUTPUT.store(true, Ordering::SeqCst);
+            }
+            "--tsv-output" => TSV_OUTPUT.store(true, Ordering::SeqCst),
+            "--sniff" => SNIFF.store(true, Ordering::SeqCst),
+            "--line-buffered" => LINE_BUFFERED.store(true, Ordering::SeqCst),
+            "--trim-headers" => TRIM_HEADERS.store(true, Ordering::SeqCst),
+            "--uniquify-headers" => {
+                UNIQUIFY_HEADERS.store(true, Ordering::SeqCst);
+            }
+            "--no-final-newline" => {
+                NO_FINAL_NEWLINE.store(true, Ordering::SeqCst);
+            }
+            "--" => {
+                args.push(arg);
+                args.extend(it);
+                break;
+            }
+            _ => args.push(arg),
+        }
+    }
+    args
+}
+
+/// Returns the options described in `usage` that take a value, e.g. `-o`
+/// and `--output` for `-o, --output <file>`.
+fn options_with_values(usage: &str) -> Vec<&str> {
+    let mut opts = vec![];
+    for line in usage.lines() {
+        let line = line.trim();
+        if !line.starts_with('-') {
+            continue;
+        }
+        // The description starts after at least two spaces.
+        let spec = line.split("  ").next().unwrap();
+        let words: Vec<&str> = spec
+            .split(&[' ', ',', '='][..])
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.iter().all(|w| w.starts_with('-')) {
+            continue;
+        }
+        opts.extend(words.into_iter().filter(|w| w.starts_with('-')));
+    }
+    opts
+}
+
+/// Returns true if the argument following `arg` is the value of an option in
+/// `opts`.
+///
+/// In a group of short flags like `-ns`, only the last one can take the
+/// next argument as its value.
+fn takes_value(arg: &str, opts: &[&str]) -> bool {
+    if arg.starts_with("--") {
+        return opts.contains(&arg);
+    }
+    if !arg.starts_with('-') {
+        return false;
+    }
+    for (i, c) in arg.char_indices().skip(1) {
+        if opts.contains(&&*format!("-{}", c)) {
+            return i + c.len_utf8() == arg.len();
+        }
+    }
+    false
+}
+
+/// Returns true if CSV data should be read as tab delimited because of the
+/// `--tsv` flag.
+pub fn tsv_input() -> bool {
+    TSV_INPUT.load(Ordering::SeqCst)
+}
+
+/// Returns true if CSV data should be written as tab delimited because of
+/// the `--tsv` or `--tsv-output` flags.
+pub fn tsv_output() -> bool {
+    TSV_OUTPUT.load(Ordering::SeqCst)
+}
+
+/// Returns true if the delimiter of CSV data should be guessed because of
+/// the `--sniff` flag.
+pub fn sniff() -> bool {
+    SNIFF.load(Ordering::SeqCst)
+}
+
+/// Returns true if output written to <stdout> should be flushed after every
+/// record because of the `--line-buffered` flag.
+pub fn line_buffered() -> bool {
+    LINE_BUFFERED.load(Ordering::SeqCst)
+}
+
+/// Returns true if whitespace around the names of the header row should be
+/// removed because of the `--trim-headers` flag.
+pub fn trim_headers() -> bool {
+    TRIM_HEADERS.load(Ordering::SeqCst)
+}
+
+/// Returns true if repeated names in the header row should be made unique
+/// because of the `--uniquify-headers` flag.
+pub fn uniquify_headers() -> bool {
+    UNIQUIFY_HEADERS.load(Ordering::SeqCst)
+}
+
+/// Returns true if no record terminator should be written after the last
+/// record because of the `--no-final-newline` flag.
+pub fn no_final_newline() -> bool {
+    NO_FINAL_NEWLINE.load(Ordering::SeqCst)
+}
+
+/// Makes the names in `headers` unique by appending `_2`, `_3`, etc. to
+/// every name that was already seen.
+///
+/// The first occurrence of a name is kept as is. A suffixed name never
+/// clashes with another name of the header row, e.g., `id,id,id_2` becomes
+/// `id,id_3,id_2`.
+pub fn unique_headers(headers: &csv::ByteRecord) -> csv::ByteRecord {
+    let names: HashSet<&[u8]> = headers.iter().collect();
+    let mut seen: HashSet<Vec<u8>> = HashSet::new();
+    let mut unique = csv::ByteRecord::new();
+    for name in headers {
+        if seen.insert(name.to_vec()) {
+            unique.push_field(name);
+            continue;
+        }
+        let mut n = 2;
+        let candidate = loop {
+            let mut candidate = name.to_vec();
+            candidate.extend(format!("_{}", n).as_bytes());
+            if !names.contains(&*candidate) && !seen.contains(&candidate) {
+                break candidate;
+            }
+            n += 1;
+        };
+        unique.push_field(&candidate);
+        seen.insert(candidate);
+    }
+    unique
+}
+
+/// Guesses the field delimiter of CSV data from a sample of its beginning.
+///
+/// Each of `,`, `;`, tab and `|` is tried, and the one that splits the most
+/// records into the same number of fields (more than one) wins. Ties go to
+/// the delimiter with more fields and then to the earlier one in that list.
+/// When `truncated` is true, the last record of the sample is ignored since
+/// it may be incomplete.
+pub fn sniff_delimiter(sample: &[u8], truncated: bool) -> Option<u8> {
+    let mut best: Option<(u8, (usize, usize))> = None;
+    for &delim in b",;\t|" {
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(delim)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(sample);
+        let mut lens = vec![];
+        for r in rdr.byte_records() {
+            match r {
+                Ok(r) => lens.push(r.len()),
+                Err(_) => break,
+            }
+        }
+        if truncated {
+            lens.pop();
+        }
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for len in lens {
+            *counts.entry(len).or_insert(0) += 1;
+        }
+        let score = counts.into_iter()
+            .filter(|&(len, _)| len > 1)
+            .map(|(len, count)| (count, len))
+            .max();
+        let score = match score {
+            None => continue,
+            Some(score) => score,
+        };
+        best = match best {
+            Some((_, best_score)) if best_score >= score => best,
+            _ => Some((delim, score)),
+        };
+    }
+    best.map(|(delim, _)| delim)
+}
+
 pub fn many_configs(inps: &[String], delim: Option<Delimiter>,
                     no_headers: bool) -> Result<Vec<Config>, String> {
     let mut inps = inps.to_vec();
@@ -87,6 +322,104 @@ pub fn last_modified(md: &fs::Metadata) -> u64 {
     FileTime::from_last_modification_time(md).seconds_relative_to_1970()
 }
 
+/// Returns a 64-bit FNV-1a hash of `bytes`.
+///
+/// Unlike the hasher in the standard library, this is guaranteed to produce
+/// the same value on every platform and in every build of xsv, which makes
+/// it suitable for anything that persists, like the name of an output file.
+pub fn fnv_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// An incremental version of `fnv_hash`, for hashing data in pieces.
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> FnvHasher {
+        FnvHasher(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Shortens `val` so that it takes at most `max` columns on a terminal,
+/// replacing the end with an ellipsis if need be.
+///
+/// If `val` isn't UTF-8 encoded, then each byte counts as one column.
+pub fn unicode_aware_ellipsis<'a>(
+    val: Cow<'a, [u8]>,
+    max: usize,
+) -> Cow<'a, [u8]> {
+    let short = match str::from_utf8(&val) {
+        Ok(s) if UnicodeWidthStr::width(s) <= max => None,
+        Ok(s) => {
+            let mut short = String::new();
+            let mut width = 0;
+            for c in s.chars() {
+                let w = UnicodeWidthChar::width(c).unwrap_or(0);
+                if width + w + 1 > max {
+                    break;
+                }
+                short.push(c);
+                width += w;
+            }
+            short.push('…');
+            Some(short.into_bytes())
+        }
+        Err(_) if val.len() <= max => None,
+        Err(_) => {
+            let mut short = val[..max.saturating_sub(1)].to_vec();
+            short.extend("…".as_bytes());
+            Some(short)
+        }
+    };
+    match short {
+        None => val,
+        Some(short) => Cow::Owned(short),
+    }
+}
+
+/// Returns true if `s` contains a character of a right-to-left script, like
+/// Hebrew or Arabic.
+pub fn has_rtl(s: &str) -> bool {
+    let ranges = [
+        (0x0590, 0x08FF), (0xFB1D, 0xFDFF), (0xFE70, 0xFEFF),
+        (0x10800, 0x10FFF), (0x1E800, 0x1EFFF),
+    ];
+    s.chars().any(|c| {
+        let c = c as u32;
+        ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi)
+    })
+}
+
+/// Appends a left-to-right mark to `val` if it contains right-to-left text.
+///
+/// Otherwise, a terminal may display the text that follows `val` on the same
+/// line, like the next columns of a table, as part of the right-to-left run,
+/// which breaks the alignment.
+pub fn rtl_safe<'a>(val: Cow<'a, [u8]>) -> Cow<'a, [u8]> {
+    match str::from_utf8(&val) {
+        Ok(s) if has_rtl(s) => {}
+        _ => return val,
+    }
+    let mut val = val.into_owned();
+    val.extend("\u{200E}".as_bytes());
+    Cow::Owned(val)
+}
+
 pub fn condense<'a>(val: Cow<'a, [u8]>, n: Option<usize>) -> Cow<'a, [u8]> {
     match n {
         None => val,
@@ -146,6 +479,112 @@ pub fn range(start: Idx, end: Idx, len: Idx, index: Idx)
     }
 }
 
+/// Returns true if stderr is connected to a terminal.
+pub fn is_stderr_tty() -> bool {
+    unsafe { libc::isatty(2) != 0 }
+}
+
+/// Progress periodically reports the number of records processed on stderr.
+///
+/// Reporting only happens when it was requested and stderr is a terminal,
+/// so that it never pollutes redirected output. Clones share the same
+/// counter, which lets parallel jobs report their combined progress.
+#[derive(Clone)]
+pub struct Progress {
+    state: Option<Arc<ProgressState>>,
+}
+
+struct ProgressState {
+    count: AtomicUsize,
+    total: Option<u64>,
+    what: &'static str,
+    start: time::Instant,
+    last_report: Mutex<time::Instant>,
+}
+
+impl Progress {
+    /// Create a new progress reporter. When `total` is known (e.g., from an
+    /// index), a percentage and an estimate of the remaining time are shown
+    /// as well.
+    pub fn new(enabled: bool, total: Option<u64>) -> Progress {
+        Progress::labeled(enabled, total, "records processed")
+    }
+
+    /// Like `new`, but the count is described by `what` (e.g., "records
+    /// written") instead of "records processed".
+    pub fn labeled(
+        enabled: bool,
+        total: Option<u64>,
+        what: &'static str,
+    ) -> Progress {
+        if !enabled || !is_stderr_tty() {
+            return Progress { state: None };
+        }
+        let now = time::Instant::now();
+        Progress {
+            state: Some(Arc::new(ProgressState {
+                count: AtomicUsize::new(0),
+                total: total,
+                what: what,
+                start: now,
+                last_report: Mutex::new(now),
+            })),
+        }
+    }
+
+    /// Record that one more record has been processed.
+    pub fn inc(&self) {
+        let state = match self.state {
+            None => return,
+            Some(ref state) => state,
+        };
+        let count = state.count.fetch_add(1, Ordering::Relaxed) + 1;
+        if count % 1024 != 0 {
+            return;
+        }
+        // If another thread is reporting, then just let it.
+        if let Ok(mut last) = state.last_report.try_lock() {
+            if last.elapsed() >= time::Duration::from_millis(200) {
+                *last = time::Instant::now();
+                state.report(count as u64);
+            }
+        }
+    }
+
+    /// Report the final count and end the progress line.
+    pub fn finish(&self) {
+        if let Some(ref state) = self.state {
+            let count = state.count.load(Ordering::Relaxed) as u64;
+            state.report(count);
+            eprintln!();
+        }
+    }
+}
+
+impl ProgressState {
+    fn report(&self, count: u64) {
+        match self.total {
+            Some(total) if total > 0 => {
+                let pct = 100.0 * (count as f64) / (total as f64);
+                let elapsed = self.start.elapsed();
+                let secs = elapsed.as_secs() as f64
+                           + elapsed.subsec_nanos() as f64 / 1e9;
+                let left = total.saturating_sub(count) as f64;
+                let eta = if count == 0 {
+                    0.0
+                } else {
+                    secs / (count as f64) * left
+                };
+                // Pad the line so that a shorter report erases the end of a
+                // longer one.
+                eprint!("\r{} of {} {} ({:.1}%, {:.0}s left)    ",
+                        count, total, self.what, pct, eta);
+            }
+            _ => eprint!("\r{} {}", count, self.what),
+        }
+    }
+}
+
 /// Create a directory recursively, avoiding the race conditons fixed by
 /// https://github.com/rust-lang/rust/pull/39799.
 fn create_dir_all_threadsafe(path: &Path) -> io::Result<()> {
@@ -187,7 +626,7 @@ impl FilenameTemplate {
     /// that we do not output headers; the caller must do that if
     /// desired.
     pub fn writer<P>(&self, path: P, unique_value: &str)
-                 -> io::Result<csv::Writer<Box<io::Write+'static>>>
+                 -> io::Result<Writer<Box<io::Write+'static>>>
         where P: AsRef<Path>
     {
         let filename = self.filename(unique_value);
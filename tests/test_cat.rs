@@ -70,6 +70,123 @@ fn cat_rows_headers() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn cat_rows_check_headers() {
+    let rows1 = vec![svec!["h1", "h2"], svec!["a", "b"]];
+    let rows2 = vec![svec!["h1", "h2"], svec!["y", "z"]];
+
+    let mut expected = rows1.clone();
+    expected.extend(rows2.clone().into_iter().skip(1));
+
+    let got: Vec<Vec<String>> = run_cat("cat_rows_check_headers", "rows",
+                                        rows1, rows2, |cmd| {
+        cmd.arg("--check-headers");
+    });
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cat_rows_check_headers_reordered() {
+    let wrk = Workdir::new("cat_rows_check_headers_reordered");
+    wrk.create("in1.csv", vec![svec!["h1", "h2"], svec!["a", "b"]]);
+    wrk.create("in2.csv", vec![svec!["h2", "h1"], svec!["z", "y"]]);
+
+    let mut cmd = wrk.command("cat");
+    cmd.args(&["rows", "--check-headers", "in1.csv", "in2.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn cat_rows_by_name() {
+    let rows1 = vec![svec!["h1", "h2", "h3"], svec!["a", "b", "c"]];
+    let rows2 = vec![svec!["h3", "h1", "h2"], svec!["z", "x", "y"]];
+    let got: Vec<Vec<String>> = run_cat("cat_rows_by_name", "rows",
+                                        rows1, rows2, |cmd| {
+        cmd.arg("--by-name");
+    });
+    let expected = vec![
+        svec!["h1", "h2", "h3"],
+        svec!["a", "b", "c"],
+        svec!["x", "y", "z"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cat_rows_by_name_duplicates() {
+    let rows1 = vec![svec!["id", "v", "id"], svec!["1", "a", "2"]];
+    let rows2 = vec![svec!["v", "id", "id"], svec!["b", "3", "4"]];
+    let got: Vec<Vec<String>> = run_cat("cat_rows_by_name_duplicates",
+                                        "rows", rows1, rows2, |cmd| {
+        cmd.arg("--by-name");
+    });
+    let expected = vec![
+        svec!["id", "v", "id"],
+        svec!["1", "a", "2"],
+        svec!["3", "b", "4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cat_rows_by_name_incompatible() {
+    let wrk = Workdir::new("cat_rows_by_name_incompatible");
+    wrk.create("in1.csv", vec![svec!["h1", "h2"], svec!["a", "b"]]);
+    wrk.create("in2.csv", vec![svec!["h1", "h3"], svec!["y", "z"]]);
+
+    let mut cmd = wrk.command("cat");
+    cmd.args(&["rows", "--by-name", "in1.csv", "in2.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn cat_rows_source() {
+    let rows1 = vec![svec!["h1", "h2"], svec!["a", "b"]];
+    let rows2 = vec![svec!["h1", "h2"], svec!["y", "z"], svec!["w", "x"]];
+    let got: Vec<Vec<String>> = run_cat("cat_rows_source", "rows",
+                                        rows1, rows2, |cmd| {
+        cmd.args(&["--source", "file"]);
+    });
+    let expected = vec![
+        svec!["file", "h1", "h2"],
+        svec!["in1.csv", "a", "b"],
+        svec!["in2.csv", "y", "z"],
+        svec!["in2.csv", "w", "x"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cat_rows_source_no_headers() {
+    let rows1 = vec![svec!["a", "b"]];
+    let rows2 = vec![svec!["y", "z"]];
+    let got: Vec<Vec<String>> = run_cat("cat_rows_source_no_headers", "rows",
+                                        rows1, rows2, |cmd| {
+        cmd.args(&["--source", "file", "--no-headers"]);
+    });
+    let expected = vec![
+        svec!["in1.csv", "a", "b"],
+        svec!["in2.csv", "y", "z"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cat_rows_source_named_like_a_global_flag() {
+    let rows1 = vec![svec!["h1", "h2"], svec!["a", "b"]];
+    let rows2 = vec![svec!["h1", "h2"], svec!["y", "z"]];
+    let got: Vec<Vec<String>> = run_cat("cat_rows_source_global", "rows",
+                                        rows1, rows2, |cmd| {
+        cmd.arg("--source").arg("--sniff");
+    });
+    let expected = vec![
+        svec!["--sniff", "h1", "h2"],
+        svec!["in1.csv", "a", "b"],
+        svec!["in2.csv", "y", "z"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn prop_cat_cols() {
     fn p(rows1: CsvData, rows2: CsvData) -> bool {
@@ -102,6 +219,28 @@ fn cat_cols_headers() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn cat_cols_pad_many() {
+    let wrk = Workdir::new("cat_cols_pad_many");
+    wrk.create("in1.csv", vec![svec!["h1"], svec!["a"], svec!["b"]]);
+    wrk.create("in2.csv", vec![svec!["h2", "h3"], svec!["c", "d"]]);
+    wrk.create("in3.csv",
+               vec![svec!["h4"], svec!["e"], svec!["f"], svec!["g"]]);
+
+    let mut cmd = wrk.command("cat");
+    cmd.arg("columns").arg("--pad")
+       .arg("in1.csv").arg("in2.csv").arg("in3.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["h1", "h2", "h3", "h4"],
+        svec!["a", "c", "d", "e"],
+        svec!["b", "", "", "f"],
+        svec!["", "", "", "g"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn cat_cols_no_pad() {
     let rows1 = vec![svec!["a", "b"]];
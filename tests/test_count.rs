@@ -60,3 +60,64 @@ fn prop_count_indexed_headers() {
     }
     qcheck(p as fn(CsvData) -> bool);
 }
+
+#[test]
+fn count_lines() {
+    let wrk = Workdir::new("count_lines");
+    wrk.create_from_string("in.csv", "h1,h2\na,\"b\nc\"\nd,e");
+
+    let mut cmd = wrk.command("count");
+    cmd.arg("in.csv");
+    let got: usize = wrk.stdout(&mut cmd);
+    assert_eq!(got, 2);
+
+    let mut cmd = wrk.command("count");
+    cmd.args(&["--lines", "in.csv"]);
+    let got: usize = wrk.stdout(&mut cmd);
+    assert_eq!(got, 4);
+}
+
+#[test]
+fn count_bytes() {
+    let wrk = Workdir::new("count_bytes");
+    wrk.create_from_string("in.csv", "h1,h2\na,\"b\nc\"\n");
+
+    let mut cmd = wrk.command("count");
+    cmd.args(&["--bytes", "in.csv"]);
+    let got: usize = wrk.stdout(&mut cmd);
+    assert_eq!(got, 14);
+}
+
+fn count_distinct_data(wrk: &Workdir) {
+    wrk.create("in.csv", vec![
+        svec!["name", "city"],
+        svec!["alice", "paris"],
+        svec!["bob", "paris"],
+        svec!["alice", "paris"],
+        svec!["alice", "lyon"],
+        svec!["", "lyon"],
+        svec!["", "lyon"],
+    ]);
+}
+
+#[test]
+fn count_distinct() {
+    let wrk = Workdir::new("count_distinct");
+    count_distinct_data(&wrk);
+
+    let mut cmd = wrk.command("count");
+    cmd.args(&["--distinct", "name", "in.csv"]);
+    let got: usize = wrk.stdout(&mut cmd);
+    assert_eq!(got, 3);
+}
+
+#[test]
+fn count_distinct_multiple() {
+    let wrk = Workdir::new("count_distinct_multiple");
+    count_distinct_data(&wrk);
+
+    let mut cmd = wrk.command("count");
+    cmd.args(&["--distinct", "name,city", "in.csv"]);
+    let got: usize = wrk.stdout(&mut cmd);
+    assert_eq!(got, 4);
+}
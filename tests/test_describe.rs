@@ -0,0 +1,49 @@
+use workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name", "score"],
+        svec!["1", "a", "1.5"],
+        svec!["2", "b", ""],
+        svec!["3", "a", "2"],
+        svec!["4", "", "3"],
+    ]
+}
+
+#[test]
+fn describe() {
+    let wrk = Workdir::new("describe");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("describe");
+    cmd.arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+records  4
+columns  3
+
+field  type     empty  distinct
+id     Integer  0.0%   4
+name   Unicode  25.0%  3
+score  Float    25.0%  4";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn describe_sample_no_headers() {
+    let wrk = Workdir::new("describe_sample_no_headers");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("describe");
+    cmd.args(&["--sample", "2", "--no-headers", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+records  2
+columns  3
+
+field  type     empty  distinct
+1      Unicode  0.0%   2
+2      Unicode  0.0%   2
+3      Unicode  0.0%   2";
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,73 @@
+use workdir::Workdir;
+
+fn old() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name", "city"],
+        svec!["1", "Alice", "Boston"],
+        svec!["2", "Bob", "Paris"],
+        svec!["3", "Carol", "Tokyo"],
+        svec!["4", "Dave", "Lima"],
+    ]
+}
+
+fn new() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name", "city"],
+        svec!["1", "Alice", "Boston"],
+        svec!["3", "Carole", "Kyoto"],
+        svec!["5", "Eve", "Oslo"],
+        svec!["2", "Bob", "Rome"],
+    ]
+}
+
+#[test]
+fn diff() {
+    let wrk = Workdir::new("diff");
+    wrk.create("old.csv", old());
+    wrk.create("new.csv", new());
+    let mut cmd = wrk.command("diff");
+    cmd.args(&["--key", "id", "old.csv", "new.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["status", "id", "name", "city", "changed"],
+        svec!["changed", "3", "Carole", "Kyoto", "name,city"],
+        svec!["added", "5", "Eve", "Oslo", ""],
+        svec!["changed", "2", "Bob", "Rome", "city"],
+        svec!["removed", "4", "Dave", "Lima", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_composite_key_no_headers() {
+    let wrk = Workdir::new("diff_composite_key_no_headers");
+    wrk.create("old.csv", vec![
+        svec!["a", "x", "1"],
+        svec!["a", "y", "2"],
+    ]);
+    wrk.create("new.csv", vec![
+        svec!["a", "y", "3"],
+        svec!["b", "x", "1"],
+    ]);
+    let mut cmd = wrk.command("diff");
+    cmd.args(&["--no-headers", "-k", "1-2", "old.csv", "new.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["changed", "a", "y", "3", "3"],
+        svec!["added", "b", "x", "1", ""],
+        svec!["removed", "a", "x", "1", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_different_columns() {
+    let wrk = Workdir::new("diff_different_columns");
+    wrk.create("old.csv", vec![svec!["id", "a"], svec!["1", "x"]]);
+    wrk.create("new.csv", vec![svec!["id"], svec!["1"]]);
+    let mut cmd = wrk.command("diff");
+    cmd.args(&["--key", "id", "old.csv", "new.csv"]);
+    wrk.assert_err(&mut cmd);
+}
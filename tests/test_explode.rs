@@ -0,0 +1,110 @@
+use workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "items", "note"],
+        svec!["1", r#"[{"sku": "a", "qty": 2}, {"sku": "b"}]"#, "x"],
+        svec!["2", "[]", "y"],
+        svec!["3", r#"[{"sku": "c", "dim": {"w": 1.5, "h": null}}]"#, "z"],
+    ]
+}
+
+#[test]
+fn explode() {
+    let wrk = Workdir::new("explode");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("explode");
+    cmd.args(&["items", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "dim.h", "dim.w", "qty", "sku", "note"],
+        svec!["1", "", "", "2", "a", "x"],
+        svec!["1", "", "", "", "b", "x"],
+        svec!["2", "", "", "", "", "y"],
+        svec!["3", "", "1.5", "", "c", "z"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn explode_first_column() {
+    let wrk = Workdir::new("explode_first_column");
+    wrk.create("in.csv", vec![
+        svec!["items", "id"],
+        svec![r#"[{"sku": "a"}, {"sku": "b"}]"#, "1"],
+    ]);
+    let mut cmd = wrk.command("explode");
+    cmd.args(&["items", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["sku", "id"],
+        svec!["a", "1"],
+        svec!["b", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn explode_no_headers() {
+    let wrk = Workdir::new("explode_no_headers");
+    wrk.create("in.csv", vec![
+        svec!["1", r#"[{"a": [1, 2]}, {"a": true}]"#],
+    ]);
+    let mut cmd = wrk.command("explode");
+    cmd.args(&["--no-headers", "2", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["1", "[1,2]"], svec!["1", "true"]];
+    assert_eq!(got, expected);
+}
+
+fn bad_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "items"],
+        svec!["1", r#"[{"a": "b"}]"#],
+        svec!["2", "not json"],
+        svec!["3", r#"{"a": "c"}"#],
+        svec!["4", "[1]"],
+    ]
+}
+
+#[test]
+fn explode_on_error_fail() {
+    let wrk = Workdir::new("explode_on_error_fail");
+    wrk.create("in.csv", bad_data());
+    let mut cmd = wrk.command("explode");
+    cmd.args(&["items", "in.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn explode_on_error_skip() {
+    let wrk = Workdir::new("explode_on_error_skip");
+    wrk.create("in.csv", bad_data());
+    let mut cmd = wrk.command("explode");
+    cmd.args(&["items", "--on-error", "skip", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "a"], svec!["1", "b"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn explode_on_error_keep() {
+    let wrk = Workdir::new("explode_on_error_keep");
+    wrk.create("in.csv", bad_data());
+    let mut cmd = wrk.command("explode");
+    cmd.args(&["items", "--on-error", "keep", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "a"],
+        svec!["1", "b"],
+        svec!["2", ""],
+        svec!["3", ""],
+        svec!["4", ""],
+    ];
+    assert_eq!(got, expected);
+}
@@ -82,3 +82,52 @@ h2  st...\
 ";
     assert_eq!(got, expected.to_string());
 }
+
+#[test]
+fn flatten_number() {
+    let (wrk, mut cmd) = setup("flatten_number");
+    cmd.arg("--number");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+record 0
+h1  abcdef
+h2  ghijkl
+#
+record 1
+h1  mnopqr
+h2  stuvwx\
+";
+    assert_eq!(got, expected.to_string());
+}
+
+#[test]
+fn flatten_number_start() {
+    let wrk = Workdir::new("flatten_number_start");
+    wrk.create("in.csv", vec![
+        svec!["h"], svec!["a"], svec!["b"], svec!["c"], svec!["d"],
+    ]);
+    let mut cmd = wrk.command("flatten");
+    cmd.args(&["--number", "--start", "1", "--limit", "2", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+record 1
+h   b
+#
+record 2
+h   c\
+";
+    assert_eq!(got, expected.to_string());
+}
+
+#[test]
+fn flatten_rtl_safe() {
+    let wrk = Workdir::new("flatten_rtl_safe");
+    wrk.create("in.csv", vec![svec!["اسم", "h"], svec!["x", "مرحبا"]]);
+    let mut cmd = wrk.command("flatten");
+    cmd.arg("--rtl-safe").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "اسم\u{200E}  x\nh    مرحبا\u{200E}");
+}
@@ -69,3 +69,26 @@ fn fmt_quote_always() {
 \"mnopqr\",\"stuvwx\"";
     assert_eq!(got, expected.to_string());
 }
+
+#[test]
+fn fmt_no_final_newline() {
+    let (wrk, mut cmd) = setup("fmt_no_final_newline");
+    cmd.arg("--no-final-newline");
+
+    let got = wrk.output(&mut cmd).stdout;
+    let expected = "\
+h1,h2
+abcdef,ghijkl
+mnopqr,stuvwx";
+    assert_eq!(String::from_utf8(got).unwrap(), expected.to_string());
+}
+
+#[test]
+fn fmt_no_final_newline_crlf() {
+    let (wrk, mut cmd) = setup("fmt_no_final_newline_crlf");
+    cmd.arg("--no-final-newline").arg("--crlf");
+
+    let got = wrk.output(&mut cmd).stdout;
+    let expected = "h1,h2\r\nabcdef,ghijkl\r\nmnopqr,stuvwx";
+    assert_eq!(String::from_utf8(got).unwrap(), expected.to_string());
+}
@@ -124,6 +124,52 @@ fn frequency_select() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn frequency_progress() {
+    let (wrk, mut cmd) = setup("frequency_progress");
+    cmd.args(&["--limit", "0"]).arg("--progress");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort();
+    let (wrk, mut cmd) = setup("frequency_progress");
+    cmd.args(&["--limit", "0"]);
+    let mut expected: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    expected.sort();
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_head() {
+    let (wrk, mut cmd) = setup("frequency_head");
+    cmd.args(&["--limit", "0", "--select", "h2", "--head", "3"]);
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort();
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["h2", "y", "2"],
+        svec!["h2", "z", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_head_index() {
+    let (wrk, mut cmd) = setup("frequency_head_index");
+    wrk.run(wrk.command("index").arg("in.csv"));
+    cmd.args(&["--limit", "0", "--select", "h2", "--head", "5"]);
+    cmd.args(&["--jobs", "2"]);
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort();
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["h2", "y", "2"],
+        svec!["h2", "z", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
 // This tests that a frequency table computed by `xsv` is always the same
 // as the frequency table computed in memory.
 #[test]
@@ -260,3 +306,108 @@ fn assert_eq_ftables(got: &FTables, expected: &FTables) -> bool {
     }
     true
 }
+
+#[test]
+fn frequency_select_many() {
+    let wrk = Workdir::new("frequency_select_many");
+    wrk.create("in.csv", vec![
+        svec!["h1", "h2", "h3"],
+        svec!["a", "x", "1"],
+        svec!["b", "y", "1"],
+        svec!["a", "z", "2"],
+    ]);
+    let mut cmd = wrk.command("index");
+    cmd.arg("in.csv");
+    wrk.run(&mut cmd);
+
+    for &jobs in &["1", "4"] {
+        let mut cmd = wrk.command("frequency");
+        cmd.args(&["--select", "h3,h1", "--jobs", jobs, "in.csv"]);
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        let expected = vec![
+            svec!["field", "value", "count"],
+            svec!["h1", "a", "2"],
+            svec!["h1", "b", "1"],
+            svec!["h3", "1", "2"],
+            svec!["h3", "2", "1"],
+        ];
+        assert_eq!(got, expected);
+    }
+}
+
+#[test]
+fn frequency_select_no_headers() {
+    let wrk = Workdir::new("frequency_select_no_headers");
+    wrk.create("in.csv", vec![svec!["a", "x"], svec!["b", "x"]]);
+    let mut cmd = wrk.command("frequency");
+    cmd.args(&["--no-headers", "--select", "2", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["2", "x", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+fn sorted_frequency(name: &str, flags: &[&str]) -> Vec<Vec<String>> {
+    let wrk = Workdir::new(name);
+    wrk.create("in.csv", vec![
+        svec!["h"],
+        svec!["b"],
+        svec!["c"],
+        svec!["a"],
+        svec!["c"],
+        svec!["d"],
+        svec!["d"],
+        svec!["d"],
+    ]);
+    let mut cmd = wrk.command("frequency");
+    cmd.args(flags).arg("in.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.into_iter().skip(1).map(|row| row[1..].to_vec()).collect()
+}
+
+#[test]
+fn frequency_sort_count_desc() {
+    let got = sorted_frequency("frequency_sort_count_desc", &[]);
+    let expected = vec![
+        svec!["d", "3"], svec!["c", "2"], svec!["a", "1"], svec!["b", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_sort_count_asc() {
+    let got = sorted_frequency(
+        "frequency_sort_count_asc", &["--sort", "count", "--asc"]);
+    let expected = vec![
+        svec!["a", "1"], svec!["b", "1"], svec!["c", "2"], svec!["d", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_sort_value() {
+    let got = sorted_frequency("frequency_sort_value", &["--sort", "value"]);
+    let expected = vec![
+        svec!["a", "1"], svec!["b", "1"], svec!["c", "2"], svec!["d", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_sort_value_desc() {
+    let flags = ["--sort", "value", "--desc", "-l", "2"];
+    let got = sorted_frequency("frequency_sort_value_desc", &flags);
+    let expected = vec![svec!["d", "3"], svec!["c", "2"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_asc_and_desc() {
+    let (wrk, mut cmd) = setup("frequency_asc_and_desc");
+    cmd.args(&["--asc", "--desc"]);
+    wrk.assert_err(&mut cmd);
+}
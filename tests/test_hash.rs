@@ -0,0 +1,58 @@
+use workdir::Workdir;
+
+#[test]
+fn hash_ignores_quoting() {
+    let wrk = Workdir::new("hash_ignores_quoting");
+    wrk.create_from_string("a.csv", "h1,h2\na,b c\n\"x,y\",z\n");
+    wrk.create_from_string("b.csv", "\"h1\",h2\r\n\"a\",\"b c\"\r\n\"x,y\",z");
+    wrk.create_from_string("c.csv", "h1,h2\n\"x,y\",z\na,b c\n");
+
+    let mut cmd = wrk.command("hash");
+    cmd.arg("a.csv");
+    let a: String = wrk.stdout(&mut cmd);
+    let mut cmd = wrk.command("hash");
+    cmd.arg("b.csv");
+    let b: String = wrk.stdout(&mut cmd);
+    let mut cmd = wrk.command("hash");
+    cmd.arg("c.csv");
+    let c: String = wrk.stdout(&mut cmd);
+
+    assert_eq!(a.len(), 16);
+    assert_eq!(a, b);
+    assert!(a != c);
+}
+
+#[test]
+fn hash_field_boundaries() {
+    let wrk = Workdir::new("hash_field_boundaries");
+    wrk.create_from_string("a.csv", "ab,c\n");
+    wrk.create_from_string("b.csv", "a,bc\n");
+
+    let mut cmd = wrk.command("hash");
+    cmd.arg("a.csv");
+    let a: String = wrk.stdout(&mut cmd);
+    let mut cmd = wrk.command("hash");
+    cmd.arg("b.csv");
+    let b: String = wrk.stdout(&mut cmd);
+    assert!(a != b);
+}
+
+#[test]
+fn hash_rows_select() {
+    let wrk = Workdir::new("hash_rows_select");
+    wrk.create("in.csv", vec![
+        svec!["name", "n"],
+        svec!["a", "1"],
+        svec!["a", "2"],
+        svec!["b", "1"],
+    ]);
+
+    let mut cmd = wrk.command("hash");
+    cmd.args(&["--rows", "--select", "name", "-c", "key", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    assert_eq!(got[0], svec!["name", "n", "key"]);
+    assert_eq!(got[1][..2], svec!["a", "1"][..]);
+    assert_eq!(got[1][2], got[2][2]);
+    assert!(got[1][2] != got[3][2]);
+}
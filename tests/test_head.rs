@@ -0,0 +1,38 @@
+use workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    let mut rows = vec![svec!["n"]];
+    rows.extend((0..15).map(|i| vec![i.to_string()]));
+    rows
+}
+
+#[test]
+fn head_default() {
+    let wrk = Workdir::new("head_default");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("head");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data()[..11].to_vec());
+}
+
+#[test]
+fn head_same_as_slice() {
+    let wrk = Workdir::new("head_same_as_slice");
+    wrk.create("in.csv", data());
+
+    for &(len, no_headers) in &[("3", false), ("3", true), ("100", false)] {
+        let mut head = wrk.command("head");
+        head.args(&["--len", len, "in.csv"]);
+        let mut slice = wrk.command("slice");
+        slice.args(&["--len", len, "in.csv"]);
+        if no_headers {
+            head.arg("--no-headers");
+            slice.arg("--no-headers");
+        }
+        let got: String = wrk.stdout(&mut head);
+        let expected: String = wrk.stdout(&mut slice);
+        assert_eq!(got, expected);
+    }
+}
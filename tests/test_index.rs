@@ -25,3 +25,91 @@ fn future_time(ft: FileTime) -> FileTime {
     let secs = ft.seconds_relative_to_1970();
     FileTime::from_seconds_since_1970(secs + 10_000, 0)
 }
+
+#[test]
+fn index_check() {
+    let wrk = Workdir::new("index_check");
+    wrk.create_indexed("in.csv", vec![
+        svec!["h1", "h2"],
+        svec!["a", "b"],
+        svec!["c", "d"],
+    ]);
+
+    let mut cmd = wrk.command("index");
+    cmd.arg("--check").arg("in.csv");
+    wrk.run(&mut cmd);
+}
+
+#[test]
+fn index_check_stale() {
+    let wrk = Workdir::new("index_check_stale");
+    wrk.create_indexed("in.csv", vec![
+        svec!["h1", "h2"],
+        svec!["a", "b"],
+        svec!["c", "d"],
+    ]);
+    let md = fs::metadata(wrk.path("in.csv.idx")).unwrap();
+    let mtime = FileTime::from_last_modification_time(&md);
+    let atime = FileTime::from_last_access_time(&md);
+
+    // Change the data without making it look newer than the index, so that
+    // the check has to inspect the records themselves.
+    wrk.create("in.csv", vec![
+        svec!["h1", "h2"],
+        svec!["aaaa", "b"],
+        svec!["c", "d"],
+        svec!["e", "f"],
+    ]);
+    set_file_times(wrk.path("in.csv"), atime, mtime).unwrap();
+
+    let mut cmd = wrk.command("index");
+    cmd.arg("--check").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn index_check_outdated() {
+    let wrk = Workdir::new("index_check_outdated");
+    wrk.create_indexed("in.csv", vec![svec!["h1"], svec!["a"]]);
+
+    let md = fs::metadata(wrk.path("in.csv.idx")).unwrap();
+    set_file_times(
+        wrk.path("in.csv"),
+        future_time(FileTime::from_last_modification_time(&md)),
+        future_time(FileTime::from_last_access_time(&md)),
+    ).unwrap();
+
+    let mut cmd = wrk.command("index");
+    cmd.arg("--check").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn index_check_missing() {
+    let wrk = Workdir::new("index_check_missing");
+    wrk.create("in.csv", vec![svec!["h1"], svec!["a"]]);
+
+    let mut cmd = wrk.command("index");
+    cmd.arg("--check").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn index_stats() {
+    let wrk = Workdir::new("index_stats");
+    wrk.create_indexed("in.csv", vec![
+        svec!["h1", "h2"],
+        svec!["a", "b"],
+        svec!["c", "d"],
+    ]);
+
+    let mut cmd = wrk.command("index");
+    cmd.arg("--stats").arg("in.csv");
+    let got: String = wrk.stdout(&mut cmd);
+    // One offset for each of the three rows plus the trailing length, each
+    // stored as a 64-bit integer.
+    let expected = "\
+records  2
+size     32";
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,134 @@
+use workdir::Workdir;
+
+#[test]
+fn input_report_clean() {
+    let wrk = Workdir::new("input_report_clean");
+    wrk.create_from_string("in.csv", "\
+h1,h2
+a,\"b \"\"quoted\"\"\"
+\"c\nd\",e
+");
+
+    let mut cmd = wrk.command("input");
+    cmd.arg("--report").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["record", "line", "problem"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn input_report_anomalies() {
+    let wrk = Workdir::new("input_report_anomalies");
+    wrk.create_from_string("in.csv", "\
+h1,h2,h3
+a,b,c
+d,e
+f,g \"h\" i,j
+\"k\"l,m,n
+");
+
+    let mut cmd = wrk.command("input");
+    cmd.arg("--report").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["record", "line", "problem"],
+        svec!["2", "3", "expected 3 fields but found 2"],
+        svec!["3", "4", "unescaped quote in field 2"],
+        svec!["4", "5", "unescaped quote in field 1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn input_report_unclosed_quote() {
+    let wrk = Workdir::new("input_report_unclosed_quote");
+    wrk.create_from_string("in.csv", "h1,h2\na,\"b\n");
+
+    let mut cmd = wrk.command("input");
+    cmd.arg("--report").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["record", "line", "problem"],
+        svec!["1", "2", "unclosed quote in field 2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn input_report_escape() {
+    let wrk = Workdir::new("input_report_escape");
+    wrk.create_from_string("in.csv", "h1,h2\na,\"b \\\"c\\\"\"\n");
+
+    let mut cmd = wrk.command("input");
+    cmd.args(&["--escape", "\\", "--report", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["record", "line", "problem"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn input_report_tsv() {
+    let wrk = Workdir::new("input_report_tsv");
+    wrk.create_from_string("in.tsv", "h1\th2\n\"a\"\tb\n");
+
+    let mut cmd = wrk.command("input");
+    cmd.args(&["--report", "in.tsv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["record", "line", "problem"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn input_report_sniff() {
+    let wrk = Workdir::new("input_report_sniff");
+    wrk.create_from_string("in.csv", "h1;h2;h3\n\"a\";b;c\nd;e;f\n");
+
+    let mut cmd = wrk.command("input");
+    cmd.args(&["--sniff", "--report", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["record", "line", "problem"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn input_report_terminator() {
+    let wrk = Workdir::new("input_report_terminator");
+    wrk.create_from_string("in.csv", "h1,h2;a,\"b\";c,d;");
+
+    let mut cmd = wrk.command("input");
+    cmd.args(&["--terminator", ";", "--report", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["record", "line", "problem"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn input_cr_terminated() {
+    let wrk = Workdir::new("input_cr_terminated");
+    wrk.create_from_string("in.csv", "h1,h2\ra,b\r\"c\rd\",e\r");
+
+    let mut cmd = wrk.command("input");
+    cmd.arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "h1,h2\na,b\n\"c\rd\",e");
+}
+
+#[test]
+fn input_custom_terminator() {
+    let wrk = Workdir::new("input_custom_terminator");
+    wrk.create_from_string("in.csv", "h1,h2;a,b;\"c;d\",e;");
+
+    let mut cmd = wrk.command("input");
+    cmd.args(&["--terminator", ";", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "h1,h2\na,b\nc;d,e");
+}
@@ -184,3 +184,60 @@ fn join_cross_no_headers() {
     ];
     assert_eq!(got, expected);
 }
+
+fn setup_fuzzy(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create("people.csv", vec![
+        svec!["name", "age"],
+        svec!["Jon", "30"],
+        svec!["Mary", "25"],
+        svec!["Zed", "50"],
+    ]);
+    wrk.create("emails.csv", vec![
+        svec!["name", "email"],
+        svec!["John", "john@example.com"],
+        svec!["Marie", "marie@example.com"],
+        svec!["Bob", "bob@example.com"],
+    ]);
+    wrk
+}
+
+#[test]
+fn join_fuzzy() {
+    let wrk = setup_fuzzy("join_fuzzy");
+    let mut cmd = wrk.command("join");
+    cmd.args(&["--fuzzy", "0.8"]);
+    cmd.args(&["name", "people.csv", "name", "emails.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "age", "name", "email", "match_score"],
+        svec!["Jon", "30", "John", "john@example.com", "0.9333"],
+        svec!["Mary", "25", "Marie", "marie@example.com", "0.8483"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_fuzzy_strict() {
+    let wrk = setup_fuzzy("join_fuzzy_strict");
+    let mut cmd = wrk.command("join");
+    cmd.args(&["--fuzzy", "0.9"]);
+    cmd.args(&["name", "people.csv", "name", "emails.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "age", "name", "email", "match_score"],
+        svec!["Jon", "30", "John", "john@example.com", "0.9333"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_fuzzy_bad_threshold() {
+    let wrk = setup_fuzzy("join_fuzzy_bad_threshold");
+    let mut cmd = wrk.command("join");
+    cmd.args(&["--fuzzy", "1.5"]);
+    cmd.args(&["name", "people.csv", "name", "emails.csv"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -115,6 +115,66 @@ Fort Worth
 ");
 }
 
+#[test]
+fn partition_buckets() {
+    let wrk = Workdir::new("partition_buckets");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("partition");
+    cmd.args(&["--buckets", "3"]).arg("state").arg(wrk.path(".")).arg("in.csv");
+    wrk.run(&mut cmd);
+
+    part_eq!(wrk, "bucket_0.csv", "\
+state,city
+NY,Manhatten
+NY,Buffalo
+");
+    part_eq!(wrk, "bucket_1.csv", "\
+state,city
+CA,San Francisco
+");
+    part_eq!(wrk, "bucket_2.csv", "\
+state,city
+TX,Dallas
+TX,Fort Worth
+");
+}
+
+#[test]
+fn partition_buckets_colocate() {
+    let wrk = Workdir::new("partition_buckets_colocate");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("partition");
+    cmd.args(&["--buckets", "2", "--drop"])
+        .arg("state")
+        .arg(wrk.path("."))
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    part_eq!(wrk, "bucket_0.csv", "\
+city
+Manhatten
+Buffalo
+");
+    part_eq!(wrk, "bucket_1.csv", "\
+city
+San Francisco
+Dallas
+Fort Worth
+");
+}
+
+#[test]
+fn partition_buckets_zero() {
+    let wrk = Workdir::new("partition_buckets_zero");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("partition");
+    cmd.args(&["--buckets", "0"]).arg("state").arg(wrk.path(".")).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn partition_into_new_directory() {
     let wrk = Workdir::new("partition_into_new_directory");
@@ -269,3 +329,69 @@ CA,San Francisco
 CO,Denver
 ");
 }
+
+fn composite_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["state", "city", "name"],
+        svec!["NY", "New York", "a"],
+        svec!["CA", "San Francisco", "b"],
+        svec!["NY", "Buffalo", "c"],
+        svec!["NY", "New York", "d"],
+    ]
+}
+
+#[test]
+fn partition_select() {
+    let wrk = Workdir::new("partition_select");
+    wrk.create("in.csv", composite_data());
+
+    let mut cmd = wrk.command("partition");
+    cmd.args(&["--select", "state,city"]).arg(wrk.path(".")).arg("in.csv");
+    wrk.run(&mut cmd);
+
+    part_eq!(wrk, "NY_NewYork.csv", "\
+state,city,name
+NY,New York,a
+NY,New York,d
+");
+    part_eq!(wrk, "CA_SanFrancisco.csv", "\
+state,city,name
+CA,San Francisco,b
+");
+    part_eq!(wrk, "NY_Buffalo.csv", "\
+state,city,name
+NY,Buffalo,c
+");
+}
+
+#[test]
+fn partition_select_separator_drop() {
+    let wrk = Workdir::new("partition_select_separator_drop");
+    wrk.create("in.csv", composite_data());
+
+    let mut cmd = wrk.command("partition");
+    cmd.args(&["--select", "city,state", "--separator", "-", "--drop"])
+        .arg(wrk.path("."))
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    part_eq!(wrk, "NewYork-NY.csv", "\
+name
+a
+d
+");
+    part_eq!(wrk, "Buffalo-NY.csv", "\
+name
+c
+");
+}
+
+#[test]
+fn partition_column_multiple() {
+    let wrk = Workdir::new("partition_column_multiple");
+    wrk.create("in.csv", composite_data());
+
+    let mut cmd = wrk.command("partition");
+    cmd.arg("state,city").arg(wrk.path(".")).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,123 @@
+use workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["city", "year", "kind", "population"],
+        svec!["Boston", "2000", "city", "589"],
+        svec!["Boston", "2010", "city", "617"],
+        svec!["Paris", "2010", "city", "2243"],
+        svec!["Paris", "2010", "metro", "10516"],
+        svec!["Boston", "2010", "metro", "4552"],
+    ]
+}
+
+#[test]
+fn pivot() {
+    let wrk = Workdir::new("pivot");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("pivot");
+    cmd.args(&["--index", "city,kind", "--columns", "year"]);
+    cmd.args(&["--values", "population", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "kind", "2000", "2010"],
+        svec!["Boston", "city", "589", "617"],
+        svec!["Paris", "city", "", "2243"],
+        svec!["Paris", "metro", "", "10516"],
+        svec!["Boston", "metro", "", "4552"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_no_headers() {
+    let wrk = Workdir::new("pivot_no_headers");
+    wrk.create("in.csv", data()[1..].to_vec());
+    let mut cmd = wrk.command("pivot");
+    cmd.args(&["--no-headers", "--index", "1,3", "--columns", "2"]);
+    cmd.args(&["--values", "4", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["1", "3", "2000", "2010"]);
+    assert_eq!(got[1], svec!["Boston", "city", "589", "617"]);
+    assert_eq!(got.len(), 5);
+}
+
+#[test]
+fn pivot_first() {
+    let wrk = Workdir::new("pivot_first");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("pivot");
+    cmd.args(&["--index", "city", "--columns", "year"]);
+    cmd.args(&["--values", "population", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "2000", "2010"],
+        svec!["Boston", "589", "617"],
+        svec!["Paris", "", "2243"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_sum() {
+    let wrk = Workdir::new("pivot_sum");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("pivot");
+    cmd.args(&["--index", "city", "--columns", "year"]);
+    cmd.args(&["--values", "population", "--agg", "sum", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "2000", "2010"],
+        svec!["Boston", "589", "5169"],
+        svec!["Paris", "", "12759"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_sum_not_a_number() {
+    let wrk = Workdir::new("pivot_sum_not_a_number");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("pivot");
+    cmd.args(&["--index", "year", "--columns", "kind"]);
+    cmd.args(&["--values", "city", "--agg", "sum", "in.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn pivot_many_columns() {
+    let wrk = Workdir::new("pivot_many_columns");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("pivot");
+    cmd.args(&["--index", "city", "--columns", "year,kind"]);
+    cmd.args(&["--values", "population", "in.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn pivot_max_groups() {
+    let wrk = Workdir::new("pivot_max_groups");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("pivot");
+    cmd.args(&["--index", "city", "--columns", "year"])
+       .args(&["--values", "population", "--max-groups", "2", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 3);
+}
+
+#[test]
+fn pivot_max_groups_exceeded() {
+    let wrk = Workdir::new("pivot_max_groups_exceeded");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("pivot");
+    cmd.args(&["--index", "city", "--columns", "year"])
+       .args(&["--values", "population", "--max-groups", "1", "in.csv"]);
+    let o = cmd.output().unwrap();
+    assert!(!o.status.success());
+    assert!(String::from_utf8_lossy(&o.stderr)
+            .contains("more than 1 distinct"));
+}
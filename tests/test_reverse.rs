@@ -37,3 +37,35 @@ fn prop_reverse_no_headers() {
     }
     qcheck(p as fn(CsvData) -> bool);
 }
+
+#[test]
+fn reverse_columns() {
+    let wrk = Workdir::new("reverse_columns");
+    wrk.create("in.csv", vec![
+        svec!["h1", "h2", "h3"],
+        svec!["a", "b", "c"],
+        svec!["d", "e", "f"],
+    ]);
+    let mut cmd = wrk.command("reverse");
+    cmd.arg("--columns").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["h3", "h2", "h1"],
+        svec!["c", "b", "a"],
+        svec!["f", "e", "d"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn reverse_columns_no_headers() {
+    let wrk = Workdir::new("reverse_columns_no_headers");
+    wrk.create("in.csv", vec![svec!["a", "b"], svec!["c", "d"]]);
+    let mut cmd = wrk.command("reverse");
+    cmd.arg("--columns").arg("--no-headers").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["b", "a"], svec!["d", "c"]];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,76 @@
+use workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "price", "active", "day", "name", "empty"],
+        svec!["1", "1.5", "true", "2018-01-02", "a", ""],
+        svec!["2", "", "FALSE", "2018-01-03T10:30", "b", ""],
+        svec!["3", "2", "false", "2018-02-28 10:30:59", "a", ""],
+        svec!["4", "3", "True", "", "2018-01-05", ""],
+    ]
+}
+
+#[test]
+fn schema_types() {
+    let wrk = Workdir::new("schema_types");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("schema");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "type", "nullable", "examples"],
+        svec!["id", "integer", "false", "1, 2, 3"],
+        svec!["price", "float", "true", "1.5, 2, 3"],
+        svec!["active", "boolean", "false", "true, FALSE, false"],
+        svec!["day", "date", "true",
+              "2018-01-02, 2018-01-03T10:30, 2018-02-28 10:30:59"],
+        svec!["name", "string", "false", "a, b, 2018-01-05"],
+        svec!["empty", "null", "true", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn schema_sample() {
+    let wrk = Workdir::new("schema_sample");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("schema");
+    cmd.args(&["--sample", "1", "--examples", "1", "--select", "price,name"]);
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "type", "nullable", "examples"],
+        svec!["price", "float", "false", "1.5"],
+        svec!["name", "string", "false", "a"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn schema_not_a_date() {
+    let wrk = Workdir::new("schema_not_a_date");
+    wrk.create("in.csv", vec![
+        svec!["day"],
+        svec!["2018-01-02"],
+        svec!["2018-13-02"],
+    ]);
+    let mut cmd = wrk.command("schema");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][1], "string");
+}
+
+#[test]
+fn schema_no_headers_select() {
+    let wrk = Workdir::new("schema_no_headers_select");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("schema");
+    cmd.arg("--no-headers").arg("--select").arg("3,1").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let fields: Vec<&str> = got.iter().map(|r| &*r[0]).collect();
+    assert_eq!(fields, vec!["field", "3", "1"]);
+}
@@ -149,3 +149,201 @@ fn search_invert_match_no_headers() {
     ];
     assert_eq!(got, expected);
 }
+
+const RAW_DATA: &'static str = "\
+h1,h2\r
+\"foo\",bar\r
+qux,\"x\"\"y\"
+foo,\"a
+b\"
+";
+
+#[test]
+fn search_raw() {
+    let wrk = Workdir::new("search_raw");
+    wrk.create_from_string("data.csv", RAW_DATA);
+    let mut cmd = wrk.command("search");
+    cmd.arg("--raw").arg("^foo").arg("data.csv");
+
+    let got = wrk.output(&mut cmd).stdout;
+    let expected = "h1,h2\r\n\"foo\",bar\r\nfoo,\"a\nb\"\n";
+    assert_eq!(String::from_utf8_lossy(&got), expected);
+}
+
+#[test]
+fn search_raw_no_headers() {
+    let wrk = Workdir::new("search_raw_no_headers");
+    wrk.create_from_string("data.csv", RAW_DATA);
+    let mut cmd = wrk.command("search");
+    cmd.arg("--raw").arg("--no-headers").arg("^(h1|qux)").arg("data.csv");
+
+    let got = wrk.output(&mut cmd).stdout;
+    let expected = "h1,h2\r\nqux,\"x\"\"y\"\n";
+    assert_eq!(String::from_utf8_lossy(&got), expected);
+}
+
+#[test]
+fn search_raw_large() {
+    let wrk = Workdir::new("search_raw_large");
+    let data: String = (0..5000)
+        .map(|i| format!("\"{}\",x\r\n", i))
+        .collect();
+    wrk.create_from_string("data.csv", &data);
+    let mut cmd = wrk.command("search");
+    cmd.arg("--raw").arg("x").arg("data.csv");
+
+    let got = wrk.output(&mut cmd).stdout;
+    assert_eq!(String::from_utf8_lossy(&got), data);
+}
+
+fn context_data() -> Vec<Vec<String>> {
+    let mut rows = vec![svec!["i", "v"]];
+    for i in 0..10 {
+        let v = if i == 2 || i == 4 || i == 9 { "hit" } else { "miss" };
+        rows.push(vec![i.to_string(), v.to_owned()]);
+    }
+    rows
+}
+
+#[test]
+fn search_context_huge() {
+    let wrk = Workdir::new("search_context_huge");
+    wrk.create("data.csv", context_data());
+    let mut cmd = wrk.command("search");
+    cmd.args(&["-C", "18446744073709551615", "-s", "v", "hit", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, context_data());
+}
+
+#[test]
+fn search_context() {
+    let wrk = Workdir::new("search_context");
+    wrk.create("data.csv", context_data());
+    let mut cmd = wrk.command("search");
+    cmd.args(&["-C", "1", "-s", "v", "hit", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["i", "v"],
+        svec!["1", "miss"],
+        svec!["2", "hit"],
+        svec!["3", "miss"],
+        svec!["4", "hit"],
+        svec!["5", "miss"],
+        svec!["8", "miss"],
+        svec!["9", "hit"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn search_context_group_separator() {
+    let wrk = Workdir::new("search_context_group_separator");
+    wrk.create("data.csv", context_data());
+    let mut cmd = wrk.command("search");
+    cmd.args(&["--context", "1", "--group-separator", "~~", "hit"]);
+    cmd.arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["i", "v"],
+        svec!["1", "miss"],
+        svec!["2", "hit"],
+        svec!["3", "miss"],
+        svec!["4", "hit"],
+        svec!["5", "miss"],
+        svec!["~~", ""],
+        svec!["8", "miss"],
+        svec!["9", "hit"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn search_context_raw() {
+    let wrk = Workdir::new("search_context_raw");
+    wrk.create_from_string("data.csv", "h\r\na\r\n\"b\"\r\nc\r\nd\r\ne\r\n");
+    let mut cmd = wrk.command("search");
+    cmd.args(&["--raw", "-C", "1", "--group-separator", "~", "^(a|e)$"]);
+    cmd.arg("data.csv");
+
+    let got = wrk.output(&mut cmd).stdout;
+    let expected = "h\r\na\r\n\"b\"\r\n~\nd\r\ne\r\n";
+    assert_eq!(String::from_utf8_lossy(&got), expected);
+}
+
+#[test]
+fn search_no_header_if_empty() {
+    let wrk = Workdir::new("search_no_header_if_empty");
+    wrk.create("data.csv", data(true));
+    let mut cmd = wrk.command("search");
+    cmd.arg("--no-header-if-empty").arg("nothing").arg("data.csv");
+    let got = wrk.output(&mut cmd).stdout;
+    assert!(got.is_empty());
+
+    let mut cmd = wrk.command("search");
+    cmd.arg("--no-header-if-empty").arg("--raw").arg("nothing");
+    cmd.arg("data.csv");
+    let got = wrk.output(&mut cmd).stdout;
+    assert!(got.is_empty());
+}
+
+#[test]
+fn search_no_header_if_empty_match() {
+    let wrk = Workdir::new("search_no_header_if_empty_match");
+    wrk.create_from_string("data.csv", RAW_DATA);
+    let mut cmd = wrk.command("search");
+    cmd.arg("--no-header-if-empty").arg("^qux").arg("data.csv");
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "h1,h2\nqux,\"x\"\"y\"");
+
+    let mut cmd = wrk.command("search");
+    cmd.arg("--no-header-if-empty").arg("--raw").arg("^qux");
+    cmd.arg("data.csv");
+    let got = wrk.output(&mut cmd).stdout;
+    assert_eq!(String::from_utf8_lossy(&got), "h1,h2\r\nqux,\"x\"\"y\"\n");
+}
+
+#[test]
+fn search_count_column() {
+    let wrk = Workdir::new("search_count_column");
+    wrk.create("data.csv", vec![
+        svec!["h1", "h2", "h3"],
+        svec!["none", "here", "foo"],
+        svec!["a foo", "b", "c"],
+        svec!["foofoo", "x foo", "foo"],
+    ]);
+    let mut cmd = wrk.command("search");
+    cmd.args(&["--count-column", "hits", "-s", "h1,h2", "foo", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["h1", "h2", "h3", "hits"],
+        svec!["none", "here", "foo", "0"],
+        svec!["a foo", "b", "c", "1"],
+        svec!["foofoo", "x foo", "foo", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn search_count_column_raw() {
+    let wrk = Workdir::new("search_count_column_raw");
+    wrk.create("data.csv", data(true));
+    let mut cmd = wrk.command("search");
+    cmd.args(&["--count-column", "hits", "--raw", "foo", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn search_raw_line_buffered() {
+    let wrk = Workdir::new("search_raw_line_buffered");
+    wrk.create_from_string("data.csv", RAW_DATA);
+    let mut cmd = wrk.command("search");
+    cmd.arg("--raw").arg("--line-buffered").arg("^foo").arg("data.csv");
+
+    let got = wrk.output(&mut cmd).stdout;
+    let expected = "h1,h2\r\n\"foo\",bar\r\nfoo,\"a\nb\"\n";
+    assert_eq!(String::from_utf8_lossy(&got), expected);
+}
@@ -1,3 +1,5 @@
+use std::fs;
+
 use workdir::Workdir;
 
 macro_rules! select_test {
@@ -91,6 +93,24 @@ select_test!(select_not, r#"!"h[]3"[0]"#, "!3",
              ["h1", "h2", "h4", "h1"], ["a", "b", "d", "e"]);
 select_test!(select_not_range, "!h1[1]-h2", "!5-2", ["h1"], ["a"]);
 
+select_test!(select_not_range_middle, "!h2-h4", "!2-4",
+             ["h1", "h1"], ["a", "e"]);
+select_test!(select_not_duplicate_name, "!h1", "!1",
+             ["h2", "h[]3", "h4", "h1"], ["b", "c", "d", "e"]);
+select_test!(select_not_multi, "!1,h4-", "!1,4-",
+             ["h2", "h[]3"], ["b", "c"]);
+
+#[test]
+fn select_not_everything() {
+    let wrk = Workdir::new("select_not_everything");
+    wrk.create("data.csv", data(true));
+    let mut cmd = wrk.command("select");
+    cmd.arg("!1-").arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "");
+}
+
 select_test!(select_duplicate, "h1,h1", "1,1", ["h1", "h1"], ["a", "a"]);
 select_test!(select_duplicate_range, "h1-h2,h1-h2", "1-2,1-2",
              ["h1", "h2", "h1", "h2"], ["a", "b", "a", "b"]);
@@ -104,6 +124,33 @@ select_test!(select_range_no_end_cat, "h4-,h1", "4-,1",
 select_test!(select_range_no_start_cat, "-h2,h1[1]", "-2,5",
              ["h1", "h2", "h1"], ["a", "b", "e"]);
 
+select_test!(select_range_name_to_idx, "h2-4", "2-4",
+             ["h2", "h[]3", "h4"], ["b", "c", "d"]);
+select_test!(select_range_idx_to_name, "2-h4", "2-4",
+             ["h2", "h[]3", "h4"], ["b", "c", "d"]);
+select_test!(select_range_idx_to_name_reverse, "4-h2", "4-2",
+             ["h4", "h[]3", "h2"], ["d", "c", "b"]);
+select_test!(select_range_idx_no_end, "2-", "2-",
+             ["h2", "h[]3", "h4", "h1"], ["b", "c", "d", "e"]);
+select_test!(select_range_duplicate_start, "h1-h2", "1-2",
+             ["h1", "h2"], ["a", "b"]);
+select_test!(select_range_duplicate_end, "h4-h1", "4-1",
+             ["h4", "h[]3", "h2", "h1"], ["d", "c", "b", "a"]);
+select_test!(select_range_duplicate_no_start, "-h1", "-1", ["h1"], ["a"]);
+select_test!(select_range_all, "-", "-",
+             ["h1", "h2", "h[]3", "h4", "h1"], ["a", "b", "c", "d", "e"]);
+
+#[test]
+fn select_range_open_empty() {
+    let wrk = Workdir::new("select_range_open_empty");
+    wrk.create_from_string("data.csv", "");
+    let mut cmd = wrk.command("select");
+    cmd.arg("--").arg("-").arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "");
+}
+
 select_test_err!(select_err_unknown_header, "dne");
 select_test_err!(select_err_oob_low, "0");
 select_test_err!(select_err_oob_high, "6");
@@ -114,3 +161,318 @@ select_test_err!(select_err_idx_not_int_2, "h1[a]");
 select_test_err!(select_err_unclosed_quote, r#""h1"#);
 select_test_err!(select_err_unclosed_bracket, r#""h1"[1"#);
 select_test_err!(select_err_expected_end_of_field, "a-b-");
+
+fn select_zero_based(wrk: &Workdir, selection: &str, zero_based: bool)
+                    -> Vec<Vec<String>> {
+    let mut cmd = wrk.command("select");
+    if zero_based {
+        cmd.arg("--zero-based");
+    }
+    cmd.arg("--").arg(selection).arg("data.csv");
+    wrk.read_stdout(&mut cmd)
+}
+
+#[test]
+fn select_zero_based_same_column() {
+    let wrk = Workdir::new("select_zero_based_same_column");
+    wrk.create("data.csv", data(true));
+    let one = select_zero_based(&wrk, "1,4-5", false);
+    let zero = select_zero_based(&wrk, "0,3-4", true);
+    assert_eq!(one, zero);
+    assert_eq!(zero, vec![svec!["h1", "h4", "h1"], svec!["a", "d", "e"]]);
+}
+
+#[test]
+fn select_zero_based_names() {
+    let wrk = Workdir::new("select_zero_based_names");
+    wrk.create("data.csv", data(true));
+    let got = select_zero_based(&wrk, "h2,h1[1]", true);
+    assert_eq!(got, vec![svec!["h2", "h1"], svec!["b", "e"]]);
+}
+
+#[test]
+fn select_zero_based_oob() {
+    let wrk = Workdir::new("select_zero_based_oob");
+    wrk.create("data.csv", data(true));
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--zero-based", "5", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+fn typed_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["i", "s", "f", "n", "m"],
+        svec!["1", "x", "1.5", "", "2"],
+        svec!["2", "y", "3", "", "a"],
+        svec!["3", "z", "4", "", "2.5"],
+    ]
+}
+
+#[test]
+fn select_type_number() {
+    let wrk = Workdir::new("select_type_number");
+    wrk.create("data.csv", typed_data());
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--select-type", "number", "1-", "data.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["i", "f"],
+        svec!["1", "1.5"],
+        svec!["2", "3"],
+        svec!["3", "4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_type_within_selection() {
+    let wrk = Workdir::new("select_type_within_selection");
+    wrk.create("data.csv", typed_data());
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--select-type", "number", "m,f-", "data.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["f"],
+        svec!["1.5"],
+        svec!["3"],
+        svec!["4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_type_selection_stdin() {
+    // This is the example of the help message.
+    let wrk = Workdir::new("select_type_selection_stdin");
+    let headers: Vec<String> = (1..12).map(|i| format!("c{}", i)).collect();
+    let row: Vec<String> = (1..12)
+        .map(|i| if i % 2 == 0 { "x".to_owned() } else { i.to_string() })
+        .collect();
+    wrk.create("data.csv", vec![headers, row]);
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--select-type", "number", "1-10"]);
+    cmd.stdin(fs::File::open(wrk.path("data.csv")).unwrap());
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["c1", "c3", "c5", "c7", "c9"],
+        svec!["1", "3", "5", "7", "9"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_type_stdin() {
+    let wrk = Workdir::new("select_type_stdin");
+    wrk.create("data.csv", typed_data());
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--select-type", "int"]);
+    cmd.stdin(fs::File::open(wrk.path("data.csv")).unwrap());
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["i"]);
+}
+
+#[test]
+fn select_type_sample() {
+    let wrk = Workdir::new("select_type_sample");
+    wrk.create("data.csv", typed_data());
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--select-type", "number", "--sample", "1", "1-"]);
+    cmd.arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["i", "f", "m"],
+        svec!["1", "1.5", "2"],
+        svec!["2", "3", "a"],
+        svec!["3", "4", "2.5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_tsv_stdin() {
+    let wrk = Workdir::new("select_tsv_stdin");
+    wrk.create_from_string("data", "h1\th2\na,b\tc\n");
+    let mut cmd = wrk.command("select");
+    cmd.arg("--tsv").arg("h2,h1");
+    cmd.stdin(fs::File::open(wrk.path("data")).unwrap());
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "h2\th1\nc\ta,b");
+}
+
+#[test]
+fn select_tsv_output() {
+    let wrk = Workdir::new("select_tsv_output");
+    wrk.create_from_string("data", "h1,h2\na\tb,c\n");
+    let mut cmd = wrk.command("select");
+    cmd.arg("--tsv-output").arg("h2,h1");
+    cmd.stdin(fs::File::open(wrk.path("data")).unwrap());
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "h2\th1\nc\t\"a\tb\"");
+}
+
+#[test]
+fn select_sniff_semicolon() {
+    let wrk = Workdir::new("select_sniff_semicolon");
+    wrk.create_from_string("data.csv", "h1;h2;h3\na;b,c;d\ne;f;g\n");
+    let mut cmd = wrk.command("select");
+    cmd.arg("--sniff").arg("h2").arg("data.csv");
+
+    let o = wrk.output(&mut cmd);
+    assert_eq!(String::from_utf8_lossy(&o.stdout), "h2\n\"b,c\"\nf\n");
+    assert!(String::from_utf8_lossy(&o.stderr)
+            .contains("sniffed delimiter ';'"));
+}
+
+#[test]
+fn select_sniff_tab_stdin() {
+    let wrk = Workdir::new("select_sniff_tab_stdin");
+    wrk.create_from_string("data", "h1\th2\na;b\tc,d\n");
+    let mut cmd = wrk.command("select");
+    cmd.arg("--sniff").arg("h2,h1");
+    cmd.stdin(fs::File::open(wrk.path("data")).unwrap());
+
+    let o = wrk.output(&mut cmd);
+    assert_eq!(String::from_utf8_lossy(&o.stdout), "h2,h1\n\"c,d\",a;b\n");
+    assert!(String::from_utf8_lossy(&o.stderr)
+            .contains("<stdin>: sniffed delimiter '\\t'"));
+}
+
+#[test]
+fn select_rest() {
+    let wrk = Workdir::new("select_rest");
+    wrk.create("data.csv", data(true));
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--rest", "3,1", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["h[]3", "h1", "h2", "h4", "h1"],
+        svec!["c", "a", "b", "d", "e"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_rest_no_headers() {
+    let wrk = Workdir::new("select_rest_no_headers");
+    wrk.create("data.csv", data(false));
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--rest", "--no-headers", "5,2", "data.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["e", "b", "a", "c", "d"]]);
+}
+
+#[test]
+fn select_exclude() {
+    let wrk = Workdir::new("select_exclude");
+    wrk.create("data.csv", data(true));
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--exclude", "h2,h4"]);
+    cmd.stdin(fs::File::open(wrk.path("data.csv")).unwrap());
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["h1", "h[]3", "h1"],
+        svec!["a", "c", "e"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_rest_exclude() {
+    let wrk = Workdir::new("select_rest_exclude");
+    wrk.create("data.csv", data(true));
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--rest", "--exclude", "h2", "3", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["h[]3", "h1", "h4", "h1"],
+        svec!["c", "a", "d", "e"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_exclude_from_selection() {
+    let wrk = Workdir::new("select_exclude_from_selection");
+    wrk.create("data.csv", data(false));
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--no-headers", "--exclude", "2,4", "5-1", "data.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["e", "c", "a"]]);
+}
+
+#[test]
+fn select_trim_headers() {
+    let wrk = Workdir::new("select_trim_headers");
+    wrk.create_from_string("data.csv", " name ,\tage\n a ,1\n");
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--trim-headers", "age,name", "data.csv"]);
+    let got = wrk.output(&mut cmd).stdout;
+    assert_eq!(String::from_utf8_lossy(&got), "age,name\n1, a \n");
+}
+
+#[test]
+fn select_untrimmed_headers() {
+    let wrk = Workdir::new("select_untrimmed_headers");
+    wrk.create_from_string("data.csv", " name ,age\na,1\n");
+    let mut cmd = wrk.command("select");
+    cmd.args(&["name", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn select_uniquify_headers() {
+    let wrk = Workdir::new("select_uniquify_headers");
+    wrk.create("data.csv", vec![
+        svec!["id", "name", "id"],
+        svec!["1", "a", "2"],
+    ]);
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--uniquify-headers", "id_2,id", "data.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["id_2", "id"], svec!["2", "1"]]);
+}
+
+#[test]
+fn select_uniquify_headers_clash() {
+    let wrk = Workdir::new("select_uniquify_headers_clash");
+    wrk.create("data.csv", vec![
+        svec!["id", "id", "id_2", "id"],
+        svec!["1", "2", "3", "4"],
+    ]);
+    let mut cmd = wrk.command("select");
+    cmd.args(&["--uniquify-headers", "1-", "data.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["id", "id_3", "id_2", "id_4"]);
+}
+
+#[test]
+fn select_line_buffered() {
+    let wrk = Workdir::new("select_line_buffered");
+    wrk.create_from_string("data", "h1,h2\na,\"b\nc\"\nd,e\n");
+    let mut cmd = wrk.command("select");
+    cmd.arg("h2,h1");
+    cmd.stdin(fs::File::open(wrk.path("data")).unwrap());
+    let expected = wrk.output(&mut cmd).stdout;
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("--line-buffered").arg("h2,h1");
+    cmd.stdin(fs::File::open(wrk.path("data")).unwrap());
+    let got = wrk.output(&mut cmd).stdout;
+    assert_eq!(got, expected);
+    assert_eq!(String::from_utf8_lossy(&got), "h2,h1\n\"b\nc\",a\ne,d\n");
+}
+
+#[test]
+fn select_no_final_newline() {
+    let wrk = Workdir::new("select_no_final_newline");
+    wrk.create_from_string("data.csv", "h1,h2\na,b\nc,d\n");
+    let mut cmd = wrk.command("select");
+    cmd.arg("--no-final-newline").arg("h2,h1").arg("data.csv");
+    let got = wrk.output(&mut cmd).stdout;
+    assert_eq!(String::from_utf8_lossy(&got), "h2,h1\nb,a\nd,c");
+}
@@ -142,3 +142,20 @@ fn slice_index_withindex() {
 fn slice_index_no_headers_withindex() {
     test_index("slice_index_no_headers_withindex", 1, "b", false, true);
 }
+
+#[test]
+fn slice_raw() {
+    let wrk = Workdir::new("slice_raw");
+    wrk.create_from_string("data.csv", "\
+h1,h2\r
+\"a\",b\r
+c,\"d\"\"e\"
+'f',g
+");
+    let mut cmd = wrk.command("slice");
+    cmd.arg("--raw").arg("--start").arg("1").arg("--len").arg("1");
+    cmd.arg("data.csv");
+
+    let got = wrk.output(&mut cmd).stdout;
+    assert_eq!(String::from_utf8_lossy(&got), "h1,h2\r\nc,\"d\"\"e\"\n");
+}
@@ -1,4 +1,7 @@
 use std::cmp;
+use std::fs;
+
+use filetime::{FileTime, set_file_times};
 
 use workdir::Workdir;
 
@@ -128,6 +131,227 @@ fn sort_reverse() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn sort_progress() {
+    let wrk = Workdir::new("sort_progress");
+    wrk.create("in.csv", vec![
+        svec!["N", "S"],
+        svec!["10", "a"],
+        svec!["2", "b"],
+    ]);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["-N", "--progress", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["N", "S"],
+        svec!["2", "b"],
+        svec!["10", "a"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_progress_indexed() {
+    let wrk = Workdir::new("sort_progress_indexed");
+    wrk.create_indexed("in.csv", vec![
+        svec!["N", "S"],
+        svec!["10", "a"],
+        svec!["2", "b"],
+    ]);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["-N", "--progress", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["N", "S"],
+        svec!["2", "b"],
+        svec!["10", "a"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_progress_stale_index() {
+    let wrk = Workdir::new("sort_progress_stale_index");
+    wrk.create_indexed("in.csv", vec![
+        svec!["N", "S"],
+        svec!["10", "a"],
+        svec!["2", "b"],
+    ]);
+    // Make the data look newer than its index.
+    let md = fs::metadata(wrk.path("in.csv.idx")).unwrap();
+    let secs = FileTime::from_last_modification_time(&md)
+        .seconds_relative_to_1970();
+    let later = FileTime::from_seconds_since_1970(secs + 10_000, 0);
+    set_file_times(wrk.path("in.csv"), later, later).unwrap();
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["-N", "--progress", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["N", "S"],
+        svec!["2", "b"],
+        svec!["10", "a"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_stable() {
+    let wrk = Workdir::new("sort_stable");
+    let mut rows = vec![svec!["key", "n"]];
+    for i in 0..100 {
+        rows.push(vec![(["b", "a"][i % 2]).to_owned(), i.to_string()]);
+    }
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["--stable", "-s", "key", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let mut expected = vec![svec!["key", "n"]];
+    for i in (1..100).step_by(2) {
+        expected.push(vec!["a".to_owned(), i.to_string()]);
+    }
+    for i in (0..100).step_by(2) {
+        expected.push(vec!["b".to_owned(), i.to_string()]);
+    }
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_stable_reverse() {
+    let wrk = Workdir::new("sort_stable_reverse");
+    wrk.create("in.csv", vec![
+        svec!["key", "n"],
+        svec!["1", "first"],
+        svec!["2", "second"],
+        svec!["1", "third"],
+        svec!["2", "fourth"],
+    ]);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["--stable", "-R", "-N", "-s", "key", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["key", "n"],
+        svec!["2", "second"],
+        svec!["2", "fourth"],
+        svec!["1", "first"],
+        svec!["1", "third"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_ignore_case() {
+    let wrk = Workdir::new("sort_ignore_case");
+    wrk.create("in.csv", vec![
+        svec!["word"],
+        svec!["Zebra"],
+        svec!["apple"],
+        svec!["Éclair"],
+        svec!["Banana"],
+        svec!["éclat"],
+    ]);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["-i", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["word"],
+        svec!["apple"],
+        svec!["Banana"],
+        svec!["Zebra"],
+        svec!["Éclair"],
+        svec!["éclat"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_ignore_case_numeric() {
+    let wrk = Workdir::new("sort_ignore_case_numeric");
+    wrk.create("in.csv", vec![
+        svec!["n", "word"],
+        svec!["10", "a"],
+        svec!["9", "B"],
+    ]);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["-i", "-N", "-s", "n", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "word"],
+        svec!["9", "B"],
+        svec!["10", "a"],
+    ];
+    assert_eq!(got, expected);
+}
+
+fn random_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["g", "n"],
+        svec!["a", "1"],
+        svec!["b", "2"],
+        svec!["a", "3"],
+        svec!["b", "4"],
+        svec!["a", "5"],
+        svec!["b", "6"],
+    ]
+}
+
+#[test]
+fn sort_random_seeded() {
+    let wrk = Workdir::new("sort_random_seeded");
+    wrk.create("in.csv", random_data());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["--random", "--seed", "42", "in.csv"]);
+    let got1: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got2: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got1, got2);
+
+    let expected = vec![
+        svec!["g", "n"],
+        svec!["b", "4"],
+        svec!["a", "5"],
+        svec!["a", "3"],
+        svec!["b", "2"],
+        svec!["b", "6"],
+        svec!["a", "1"],
+    ];
+    assert_eq!(got1, expected);
+}
+
+#[test]
+fn sort_random_within_groups() {
+    let wrk = Workdir::new("sort_random_within_groups");
+    wrk.create("in.csv", random_data());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(&["--random", "--seed", "42", "-s", "g", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["g", "n"],
+        svec!["a", "5"],
+        svec!["a", "3"],
+        svec!["a", "1"],
+        svec!["b", "4"],
+        svec!["b", "2"],
+        svec!["b", "6"],
+    ];
+    assert_eq!(got, expected);
+}
+
 /// Order `a` and `b` lexicographically using `Ord`
 pub fn iter_cmp<A, L, R>(mut a: L, mut b: R) -> cmp::Ordering
         where A: Ord, L: Iterator<Item=A>, R: Iterator<Item=A> {
@@ -0,0 +1,41 @@
+use workdir::Workdir;
+
+#[test]
+fn sparkline_monotonic() {
+    let wrk = Workdir::new("sparkline_monotonic");
+    // Bucket `i` gets `i + 1` values.
+    let mut data = vec![svec!["n", "s"]];
+    for i in 0..7 {
+        for _ in 0..(i + 1) {
+            data.push(vec![i.to_string(), "a".to_owned()]);
+        }
+    }
+    data.push(svec!["7", "b"]);
+    data.push(svec!["", "c"]);
+    wrk.create("in.csv", data);
+
+    let mut cmd = wrk.command("sparkline");
+    cmd.args(&["--bins", "7", "in.csv"]);
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+field  sparkline  min  max
+n      ▂▃▄▅▆▇█    0    7
+s";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sparkline_gaps() {
+    let wrk = Workdir::new("sparkline_gaps");
+    wrk.create("in.csv", vec![
+        svec!["n"], svec!["1"], svec!["1"], svec!["5"], svec!["2.5"],
+    ]);
+
+    let mut cmd = wrk.command("sparkline");
+    cmd.args(&["--bins", "4", "--select", "n", "in.csv"]);
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+field  sparkline  min  max
+n      █▅▁▅       1    5";
+    assert_eq!(got, expected);
+}
@@ -213,3 +213,385 @@ mod stats_header_fields {
     stats_test_headers!(stats_header_field_name, "field", &["a"], "header");
     stats_test_no_headers!(stats_header_no_field_name, "field", &["a"], "0");
 }
+
+#[test]
+fn stats_memory_index() {
+    let wrk = Workdir::new("stats_memory_index");
+    let mut data = vec![svec!["n", "s"]];
+    for i in 0..100 {
+        data.push(vec![i.to_string(), format!("s{}", i % 7)]);
+    }
+    // The on-disk index is made for a separate copy, so that the run with
+    // --memory-index never finds an index (which may be stale when the test
+    // is run again).
+    wrk.create("mem.csv", data.clone());
+    wrk.create_indexed("idx.csv", data);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--everything", "--jobs", "4", "--memory-index", "mem.csv"]);
+    let got: String = wrk.stdout(&mut cmd);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--everything", "--jobs", "4", "idx.csv"]);
+    let expected: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_progress() {
+    let wrk = Workdir::new("stats_progress");
+    let mut data = vec![svec!["n"]];
+    for i in 0..50 {
+        data.push(vec![i.to_string()]);
+    }
+    wrk.create_indexed("in.csv", data);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--jobs", "2", "--progress", "in.csv"]);
+    let got: String = wrk.stdout(&mut cmd);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--jobs", "2", "in.csv"]);
+    let expected: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+fn stats_head(name: &str, indexed: bool) {
+    let wrk = Workdir::new(name);
+    let mut data = vec![svec!["n"]];
+    for i in 1..21 {
+        data.push(vec![i.to_string()]);
+    }
+    if indexed {
+        wrk.create_indexed("in.csv", data);
+    } else {
+        wrk.create("in.csv", data);
+    }
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--head", "5", "--jobs", "3", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0][1..4], svec!["type", "sum", "min"][..]);
+    assert_eq!(got[1][1..4], svec!["Integer", "15", "1"][..]);
+    assert_eq!(got[1][4], "5");
+}
+
+#[test]
+fn stats_head_no_index() {
+    stats_head("stats_head_no_index", false);
+}
+
+#[test]
+fn stats_head_index() {
+    stats_head("stats_head_index", true);
+}
+
+fn stats_approx_cardinality(name: &str, indexed: bool) {
+    let wrk = Workdir::new(name);
+    let mut data = vec![svec!["n", "s"]];
+    for i in 0..20000 {
+        data.push(vec![(i % 5000).to_string(), format!("s{}", i % 3)]);
+    }
+    if indexed {
+        wrk.create_indexed("in.csv", data);
+    } else {
+        wrk.create("in.csv", data);
+    }
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--cardinality", "--approx-cardinality", "--jobs", "4"]);
+    cmd.arg("in.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0][9..], svec!["cardinality", "approx_cardinality"][..]);
+
+    let exact: f64 = got[1][9].parse().unwrap();
+    let approx: f64 = got[1][10].parse().unwrap();
+    assert_eq!(exact, 5000.0);
+    assert!((approx - exact).abs() / exact < 0.03,
+            "estimate {} is too far from {}", approx, exact);
+    assert_eq!(got[2][9..], svec!["3", "3"][..]);
+}
+
+#[test]
+fn stats_approx_cardinality_no_index() {
+    stats_approx_cardinality("stats_approx_cardinality_no_index", false);
+}
+
+#[test]
+fn stats_approx_cardinality_index() {
+    stats_approx_cardinality("stats_approx_cardinality_index", true);
+}
+
+fn stats_approx_quantiles(name: &str, indexed: bool) {
+    let wrk = Workdir::new(name);
+    let mut data = vec![svec!["n", "s"]];
+    for i in 0..20000u64 {
+        // A permutation of 0..20000, so that the values aren't sorted.
+        let n = (i * 7919) % 20000;
+        data.push(vec![n.to_string(), "s".to_owned()]);
+    }
+    if indexed {
+        wrk.create_indexed("in.csv", data);
+    } else {
+        wrk.create("in.csv", data);
+    }
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--median", "--approx-quantiles", "--jobs", "4"]);
+    cmd.arg("in.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0][9..],
+               svec!["median", "approx_q1", "approx_median",
+                     "approx_q3"][..]);
+
+    let exact: f64 = got[1][9].parse().unwrap();
+    let quantiles: Vec<f64> =
+        got[1][10..].iter().map(|v| v.parse().unwrap()).collect();
+    assert_eq!(exact, 9999.5);
+    for (&approx, &q) in quantiles.iter().zip(&[0.25, 0.5, 0.75]) {
+        let expected = q * 20000.0;
+        assert!((approx - expected).abs() < 200.0,
+                "estimate {} is too far from {}", approx, expected);
+    }
+    assert_eq!(got[2][10..], svec!["", "", ""][..]);
+}
+
+#[test]
+fn stats_approx_quantiles_no_index() {
+    stats_approx_quantiles("stats_approx_quantiles_no_index", false);
+}
+
+#[test]
+fn stats_approx_quantiles_index() {
+    stats_approx_quantiles("stats_approx_quantiles_index", true);
+}
+
+#[test]
+fn stats_approx_quantiles_small() {
+    let wrk = Workdir::new("stats_approx_quantiles_small");
+    wrk.create("in.csv", vec![
+        svec!["n"], svec!["4"], svec!["1"], svec!["3"], svec!["2"],
+    ]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--approx-quantiles", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][9..], svec!["1.5", "2.5", "3.5"][..]);
+}
+
+fn stats_top(name: &str, indexed: bool) {
+    let wrk = Workdir::new(name);
+    let mut data = vec![svec!["v"]];
+    for i in 0..3000 {
+        let v =
+            if i % 10 < 5 {
+                "a".to_owned()
+            } else if i % 10 < 8 {
+                "b".to_owned()
+            } else if i % 10 == 8 {
+                "c".to_owned()
+            } else {
+                format!("x{}", i)
+            };
+        data.push(vec![v]);
+    }
+    data.push(svec![""]);
+    if indexed {
+        wrk.create_indexed("in.csv", data);
+    } else {
+        wrk.create("in.csv", data);
+    }
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--top", "2", "--jobs", "4", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0].last().unwrap(), "top");
+    assert_eq!(got[1].last().unwrap(), "a (1500), b (900)");
+}
+
+#[test]
+fn stats_top_no_index() {
+    stats_top("stats_top_no_index", false);
+}
+
+#[test]
+fn stats_top_index() {
+    stats_top("stats_top_index", true);
+}
+
+#[test]
+fn stats_length_values() {
+    let wrk = Workdir::new("stats_length_values");
+    wrk.create("in.csv", vec![
+        svec!["s", "n"],
+        svec!["bbb", "10"],
+        svec!["a", "2"],
+        svec!["cc", "300"],
+        svec!["dd", "-1"],
+        svec!["eeeee", "4"],
+        svec!["z", "5"],
+    ]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--everything", "in.csv"]);
+    let mut rows: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let headers = rows.remove(0);
+    let col = |name: &str| headers.iter().position(|h| h == name).unwrap();
+    let (min_len, max_len) = (col("min_length"), col("max_length"));
+    let (min_val, max_val) = (col("min_length_value"), col("max_length_value"));
+
+    assert_eq!(rows[0][min_val], "a");
+    assert_eq!(rows[0][max_val], "eeeee");
+    assert_eq!(rows[1][min_val], "2");
+    assert_eq!(rows[1][max_val], "300");
+    for row in &rows {
+        assert_eq!(row[min_len], row[min_val].len().to_string());
+        assert_eq!(row[max_len], row[max_val].len().to_string());
+    }
+}
+
+#[test]
+fn stats_round() {
+    let wrk = Workdir::new("stats_round");
+    wrk.create("in.csv", vec![
+        svec!["f", "i"],
+        svec!["1.23456", "1"],
+        svec!["2.5", "2"],
+        svec!["3", "4"],
+    ]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--round", "2", "--median", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "type", "sum", "min", "max", "min_length",
+              "max_length", "mean", "stddev", "median"],
+        svec!["f", "Float", "6.73", "1.23", "3", "1", "7", "2.24", "0.74",
+              "2.5"],
+        svec!["i", "Integer", "7", "1", "4", "1", "1", "2.33", "1.25", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_null_value() {
+    let wrk = Workdir::new("stats_null_value");
+    wrk.create("in.csv", vec![
+        svec!["n", "s"],
+        svec!["1", "a"],
+        svec!["NA", "NULL"],
+        svec!["3", "b"],
+        svec!["", "NA"],
+    ]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--null-value", "NA,NULL", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "type", "sum", "min", "max", "min_length",
+              "max_length", "mean", "stddev"],
+        svec!["n", "Integer", "4", "1", "3", "0", "1", "2", "1"],
+        svec!["s", "Unicode", "", "a", "b", "0", "1", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_exclude() {
+    let wrk = Workdir::new("stats_exclude");
+    wrk.create("in.csv", vec![
+        svec!["a", "b", "c", "d"],
+        svec!["1", "x", "2", "y"],
+    ]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--exclude", "b,d", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let fields: Vec<&str> = got[1..].iter().map(|r| &*r[0]).collect();
+    assert_eq!(fields, vec!["a", "c"]);
+}
+
+fn stats_decimal(name: &str, indexed: bool) {
+    let wrk = Workdir::new(name);
+    let mut data = vec![svec!["price", "delta"]];
+    for _ in 0..1000 {
+        data.push(svec!["0.10", "-0.3"]);
+        data.push(svec!["0.2", "0.25"]);
+    }
+    if indexed {
+        wrk.create_indexed("in.csv", data);
+    } else {
+        wrk.create("in.csv", data);
+    }
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--decimal", "--jobs", "4", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][2], "300.00");
+    assert_eq!(got[2][2], "-50.00");
+}
+
+#[test]
+fn stats_decimal_no_index() {
+    stats_decimal("stats_decimal_no_index", false);
+}
+
+#[test]
+fn stats_decimal_index() {
+    stats_decimal("stats_decimal_index", true);
+}
+
+#[test]
+fn stats_decimal_small() {
+    let wrk = Workdir::new("stats_decimal_small");
+    wrk.create("in.csv", vec![
+        svec!["a", "b", "c"],
+        svec!["0.1", "-0.05", "1e-1"],
+        svec!["0.2", "3", "0.2"],
+        svec!["", "-3", ""],
+    ]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--decimal", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][2], "0.3");
+    assert_eq!(got[2][2], "-0.05");
+    // Scientific notation falls back to a floating point sum.
+    assert_eq!(got[3][2], (0.1f64 + 0.2).to_string());
+}
+
+#[test]
+fn stats_round_negative_zero() {
+    let wrk = Workdir::new("stats_round_negative_zero");
+    wrk.create("in.csv", vec![svec!["f"], svec!["-0.001"], svec!["-0.002"]]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--round", "2", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    // sum, min, max and mean.
+    assert_eq!(got[1][2..5], svec!["0", "0", "0"][..]);
+    assert_eq!(got[1][7], "0");
+}
+
+#[test]
+fn stats_round_many_places() {
+    let wrk = Workdir::new("stats_round_many_places");
+    wrk.create("in.csv", vec![svec!["f"], svec!["1.5"], svec!["2.25"]]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(&["--round", "400", "in.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][2..5], svec!["3.75", "1.5", "2.25"][..]);
+    assert_eq!(got[1][7], "1.875");
+}
+
+#[test]
+fn stats_null_value_unset() {
+    let wrk = Workdir::new("stats_null_value_unset");
+    wrk.create("in.csv", vec![svec!["n"], svec!["1"], svec!["NA"]]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("in.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][1], "Unicode");
+}
@@ -23,3 +23,95 @@ abcdefg  a    a
 a        abc  z\
 ")
 }
+
+#[test]
+fn table_wide_chars() {
+    let wrk = Workdir::new("table_wide_chars");
+    wrk.create("in.csv", vec![
+        svec!["h1", "h2"],
+        svec!["日本語", "x"],
+        svec!["abcdefgh", "y"],
+        svec!["é", "z"],
+    ]);
+
+    let mut cmd = wrk.command("table");
+    cmd.arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(&*got, "\
+h1        h2
+日本語    x
+abcdefgh  y
+é         z\
+")
+}
+
+#[test]
+fn table_breaks_in_fields() {
+    let wrk = Workdir::new("table_breaks_in_fields");
+    wrk.create("in.csv", vec![
+        svec!["h1", "h2"],
+        svec!["a\tb", "c"],
+        svec!["long\nfield", "d"],
+    ]);
+
+    let mut cmd = wrk.command("table");
+    cmd.arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(&*got, "\
+h1          h2
+a b         c
+long field  d\
+")
+}
+
+#[test]
+fn table_max_width() {
+    let wrk = Workdir::new("table_max_width");
+    wrk.create("in.csv", vec![
+        svec!["h1", "h2", "h3"],
+        svec!["abcdefgh", "日本語です", "a"],
+        svec!["abcd", "x", "b"],
+    ]);
+
+    let mut cmd = wrk.command("table");
+    cmd.arg("--width").arg("3").arg("--max-width").arg("5").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(&*got, "\
+h1     h2     h3
+abcd…  日本…  a
+abcd   x      b\
+")
+}
+
+#[test]
+fn table_max_width_less_than_width() {
+    let wrk = Workdir::new("table_max_width_less_than_width");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("table");
+    cmd.arg("--width").arg("4").arg("--max-width").arg("3").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn table_rtl_safe() {
+    let wrk = Workdir::new("table_rtl_safe");
+    wrk.create("in.csv", vec![
+        svec!["h1", "h2"],
+        svec!["שלום", "x"],
+        svec!["abcdef", "y"],
+    ]);
+
+    let mut cmd = wrk.command("table");
+    cmd.arg("--rtl-safe").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(&*got, "\
+h1      h2
+שלום\u{200E}    x
+abcdef  y\
+")
+}
@@ -0,0 +1,71 @@
+use workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    let mut rows = vec![svec!["n"]];
+    rows.extend((0..15).map(|i| vec![i.to_string()]));
+    rows
+}
+
+fn expected(len: usize) -> Vec<Vec<String>> {
+    let mut rows = vec![svec!["n"]];
+    rows.extend(data()[16 - len..].iter().cloned());
+    rows
+}
+
+#[test]
+fn tail_default() {
+    let wrk = Workdir::new("tail_default");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("tail");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected(10));
+}
+
+#[test]
+fn tail_index() {
+    let wrk = Workdir::new("tail_index");
+    wrk.create_indexed("in.csv", data());
+    let mut cmd = wrk.command("tail");
+    cmd.args(&["--len", "3", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected(3));
+}
+
+#[test]
+fn tail_more_than_all() {
+    let wrk = Workdir::new("tail_more_than_all");
+    wrk.create("in.csv", data());
+    wrk.create_indexed("in_idx.csv", data());
+    for input in &["in.csv", "in_idx.csv"] {
+        let mut cmd = wrk.command("tail");
+        cmd.args(&["-l", "100", input]);
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        assert_eq!(got, data());
+    }
+}
+
+#[test]
+fn tail_no_headers() {
+    let wrk = Workdir::new("tail_no_headers");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("tail");
+    cmd.args(&["--no-headers", "-l", "2", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["13"], svec!["14"]]);
+}
+
+#[test]
+fn tail_huge_len() {
+    let wrk = Workdir::new("tail_huge_len");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("tail");
+    cmd.arg("-l").arg("18446744073709551615").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data());
+}
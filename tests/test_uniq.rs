@@ -0,0 +1,100 @@
+use workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["city", "n"],
+        svec!["Boston", "1"],
+        svec!["Boston", "1"],
+        svec!["Boston", "2"],
+        svec!["Paris", "3"],
+        svec!["Rome", "4"],
+        svec!["Rome", "4"],
+    ]
+}
+
+#[test]
+fn uniq() {
+    let wrk = Workdir::new("uniq");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("uniq");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "n"],
+        svec!["Boston", "1"],
+        svec!["Boston", "2"],
+        svec!["Paris", "3"],
+        svec!["Rome", "4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn uniq_select_count() {
+    let wrk = Workdir::new("uniq_select_count");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("uniq");
+    cmd.arg("-c").arg("-s").arg("city").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["count", "city", "n"],
+        svec!["3", "Boston", "1"],
+        svec!["1", "Paris", "3"],
+        svec!["2", "Rome", "4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn uniq_repeated() {
+    let wrk = Workdir::new("uniq_repeated");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("uniq");
+    cmd.arg("--repeated").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "n"],
+        svec!["Boston", "1"],
+        svec!["Rome", "4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn uniq_unique() {
+    let wrk = Workdir::new("uniq_unique");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("uniq");
+    cmd.arg("--unique").arg("-s").arg("city").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "n"],
+        svec!["Paris", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn uniq_no_headers() {
+    let wrk = Workdir::new("uniq_no_headers");
+    wrk.create("in.csv", vec![svec!["a"], svec!["a"], svec!["b"]]);
+    let mut cmd = wrk.command("uniq");
+    cmd.arg("-c").arg("--no-headers").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["2", "a"], svec!["1", "b"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn uniq_repeated_and_unique() {
+    let wrk = Workdir::new("uniq_repeated_and_unique");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("uniq");
+    cmd.arg("--repeated").arg("--unique").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,63 @@
+use workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["city", "kind", "2000", "2010"],
+        svec!["Boston", "city", "589", "617"],
+        svec!["Paris", "city", "", "2243"],
+    ]
+}
+
+#[test]
+fn unpivot() {
+    let wrk = Workdir::new("unpivot");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("unpivot");
+    cmd.args(&["--id", "city,kind", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "kind", "variable", "value"],
+        svec!["Boston", "city", "2000", "589"],
+        svec!["Boston", "city", "2010", "617"],
+        svec!["Paris", "city", "2000", ""],
+        svec!["Paris", "city", "2010", "2243"],
+    ];
+    assert_eq!(got, expected);
+    // One record for each id row and melted column, plus the headers.
+    assert_eq!(got.len(), 2 * 2 + 1);
+}
+
+#[test]
+fn unpivot_value_cols() {
+    let wrk = Workdir::new("unpivot_value_cols");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("unpivot");
+    cmd.args(&["--id", "city", "--value-cols", "4,kind", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "variable", "value"],
+        svec!["Boston", "2010", "617"],
+        svec!["Boston", "kind", "city"],
+        svec!["Paris", "2010", "2243"],
+        svec!["Paris", "kind", "city"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn unpivot_no_headers() {
+    let wrk = Workdir::new("unpivot_no_headers");
+    wrk.create("in.csv", data());
+    let mut cmd = wrk.command("unpivot");
+    cmd.args(&["--id", "1", "--value-cols", "3", "--no-headers", "in.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "3", "2000"],
+        svec!["Boston", "3", "589"],
+        svec!["Paris", "3", ""],
+    ];
+    assert_eq!(got, expected);
+}
@@ -35,22 +35,34 @@ mod workdir;
 
 mod test_cat;
 mod test_count;
+mod test_describe;
+mod test_diff;
+mod test_explode;
 mod test_fixlengths;
 mod test_flatten;
 mod test_fmt;
 mod test_frequency;
+mod test_hash;
+mod test_head;
 mod test_headers;
 mod test_index;
+mod test_input;
 mod test_join;
 mod test_partition;
+mod test_pivot;
 mod test_reverse;
+mod test_schema;
 mod test_search;
 mod test_select;
 mod test_slice;
 mod test_sort;
+mod test_sparkline;
 mod test_split;
 mod test_stats;
 mod test_table;
+mod test_tail;
+mod test_uniq;
+mod test_unpivot;
 
 fn qcheck<T: Testable>(p: T) {
     QuickCheck::new().gen(StdGen::new(thread_rng(), 5)).quickcheck(p);